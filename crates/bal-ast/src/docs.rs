@@ -0,0 +1,137 @@
+//! Ballerina documentation comments
+//!
+//! A declaration is documented by the `#` lines directly before it:
+//!
+//! ```ballerina
+//! # Adds two numbers.
+//! # + a - the first operand
+//! # + b - the second operand
+//! # + return - the sum
+//! function add(int a, int b) returns int { }
+//! ```
+
+use bal_syntax::{BallerinaLanguage, SyntaxKind};
+use rowan::{NodeOrToken, SyntaxNode};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Documentation {
+    /// Markdown text before the first `# +` section
+    pub summary: String,
+    pub params: Vec<ParamDoc>,
+    /// Description from the `# + return -` section
+    pub returns: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamDoc {
+    pub name: String,
+    pub description: String,
+}
+
+/// Where the following documentation lines belong
+enum Section {
+    Summary,
+    Param(usize),
+    Return,
+}
+
+impl Documentation {
+    /// Parse the text of consecutive documentation lines, each starting with `#`
+    pub fn parse<'a>(lines: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut docs = Documentation::default();
+        let mut summary = Vec::new();
+        let mut section = Section::Summary;
+
+        for line in lines {
+            let text = line.strip_prefix('#').unwrap_or(line);
+            let text = text.strip_prefix(' ').unwrap_or(text);
+
+            if let Some(rest) = text.trim_start().strip_prefix('+') {
+                let (name, description) = split_section(rest);
+                if name == "return" {
+                    docs.returns = Some(description.to_string());
+                    section = Section::Return;
+                } else {
+                    docs.params.push(ParamDoc {
+                        name: name.to_string(),
+                        description: description.to_string(),
+                    });
+                    section = Section::Param(docs.params.len() - 1);
+                }
+                continue;
+            }
+
+            match section {
+                Section::Summary => summary.push(text.trim_end()),
+                Section::Param(index) => append_line(&mut docs.params[index].description, text),
+                Section::Return => append_line(docs.returns.get_or_insert_with(String::new), text),
+            }
+        }
+
+        docs.summary = summary.join("\n").trim().to_string();
+        docs
+    }
+
+    /// Documentation lines in the leading trivia of `node`, if there are any
+    pub fn of_node(node: &SyntaxNode<BallerinaLanguage>) -> Option<Self> {
+        let mut lines = Vec::new();
+        for element in node.children_with_tokens() {
+            let token = match element {
+                NodeOrToken::Token(token) => token,
+                NodeOrToken::Node(_) => break,
+            };
+            match token.kind() {
+                SyntaxKind::DOC_COMMENT => lines.push(token.text().to_string()),
+                // A regular comment separates the docs from the declaration
+                SyntaxKind::COMMENT => lines.clear(),
+                kind if kind.is_trivia() => {}
+                _ => break,
+            }
+        }
+
+        if lines.is_empty() {
+            None
+        } else {
+            Some(Self::parse(lines.iter().map(String::as_str)))
+        }
+    }
+}
+
+// Split the text after `# +` into the name and the description after its `-`.
+// The name ends at whitespace or a `-` that is not escaped, as in `a\-b`.
+fn split_section(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+    let mut end = text.len();
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '-' => {
+                end = i;
+                break;
+            }
+            c if c.is_whitespace() => {
+                end = i;
+                break;
+            }
+            _ => {}
+        }
+    }
+    let (name, rest) = text.split_at(end);
+    let rest = rest.trim_start();
+    (name, rest.strip_prefix('-').unwrap_or(rest).trim())
+}
+
+// Continuation lines of a `# +` section extend its description
+fn append_line(description: &mut String, line: &str) {
+    let line = line.trim();
+    if line.is_empty() {
+        return;
+    }
+    if !description.is_empty() {
+        description.push(' ');
+    }
+    description.push_str(line);
+}
@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests {
+    use crate::docs::{Documentation, ParamDoc};
+    use crate::SourceFile;
+    use bal_parser::Parser;
+    use bal_syntax::source::FileId;
+    use rowan::SyntaxNode;
+
+    fn parse(input: &str) -> SourceFile {
+        let tokens = bal_syntax::lexer::lex(FileId::default(), input).tokens;
+        let (green, _) = Parser::new(FileId::default(), input, tokens).parse();
+        SourceFile::new(SyntaxNode::new_root(green))
+    }
+
+    #[test]
+    fn test_function_docs() {
+        let source = parse(
+            r#"import ballerina/io;
+
+# Adds two numbers.
+#
+# Overflow is a panic.
+# + a - the first operand
+# + b - the second operand,
+#       which may be negative
+# + return - the sum
+public function add(int a, int b) returns int { }
+
+function undocumented() { }
+"#,
+        );
+        let functions: Vec<_> = source.functions().collect();
+        assert_eq!(functions[0].name().as_deref(), Some("add"));
+
+        let docs = functions[0].docs().expect("add is documented");
+        assert_eq!(docs.summary, "Adds two numbers.\n\nOverflow is a panic.");
+        assert_eq!(
+            docs.params,
+            vec![
+                ParamDoc {
+                    name: "a".to_string(),
+                    description: "the first operand".to_string(),
+                },
+                ParamDoc {
+                    name: "b".to_string(),
+                    description: "the second operand, which may be negative".to_string(),
+                },
+            ]
+        );
+        assert_eq!(docs.returns.as_deref(), Some("the sum"));
+
+        assert_eq!(functions[1].docs(), None);
+    }
+
+    #[test]
+    fn test_comment_separates_docs() {
+        let source = parse("# Stale docs\n// note\nfunction f() { }\n");
+        let function = source.functions().next().unwrap();
+        assert_eq!(function.docs(), None);
+    }
+
+    #[test]
+    fn test_parse_summary_only() {
+        let docs = Documentation::parse(["# Prints a greeting", "#"]);
+        assert_eq!(docs.summary, "Prints a greeting");
+        assert!(docs.params.is_empty());
+        assert_eq!(docs.returns, None);
+    }
+
+    #[test]
+    fn test_param_names_with_escaped_hyphens() {
+        let docs =
+            Documentation::parse([r"# + 'a\-b - a well-known name", "# + x-the x coordinate"]);
+        assert_eq!(
+            docs.params,
+            vec![
+                ParamDoc {
+                    name: r"'a\-b".to_string(),
+                    description: "a well-known name".to_string(),
+                },
+                ParamDoc {
+                    name: "x".to_string(),
+                    description: "the x coordinate".to_string(),
+                },
+            ]
+        );
+    }
+}
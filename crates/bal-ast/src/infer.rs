@@ -0,0 +1,130 @@
+//! The types of expressions, as far as they are known without resolving
+//! type definitions or other modules
+//!
+//! Anything whose type is not known here is `None`, and checks that need it
+//! are skipped rather than guessed.
+
+use super::types::Type;
+use super::{declared_type, first_identifier, identifier_name, Function, ParamKind, SourceFile};
+use bal_syntax::{BallerinaLanguage, SyntaxKind};
+use rowan::{NodeOrToken, SyntaxNode, SyntaxToken};
+use std::collections::HashMap;
+
+/// The values in scope with their types, and the return types of functions
+#[derive(Debug, Clone, Default)]
+pub struct Scope {
+    values: HashMap<String, Type>,
+    functions: HashMap<String, Type>,
+}
+
+impl Scope {
+    /// The constants, variables and functions of the module
+    pub fn module(source_file: &SourceFile) -> Self {
+        let mut scope = Scope::default();
+        for decl in source_file.consts() {
+            // Without a declared type, a constant has the type of its value
+            let ty = decl
+                .declared_type()
+                .or_else(|| decl.initializer().and_then(|init| scope.type_of(&init)));
+            scope.declare(decl.name(), ty);
+        }
+        for var in source_file.module_vars() {
+            scope.declare(var.name(), var.declared_type());
+        }
+        for function in source_file.functions() {
+            if let (Some(name), Some(ty)) = (function.name(), function.return_type()) {
+                scope.functions.entry(name).or_insert(ty);
+            }
+        }
+        scope
+    }
+
+    /// This scope with the parameters and local variables of `function`,
+    /// which hide module-level names
+    pub fn function(&self, function: &Function) -> Self {
+        let mut scope = self.clone();
+        for param in function.params() {
+            let ty = param.declared_type().map(|ty| match param.kind() {
+                ParamKind::Rest => Type::Array {
+                    element: Box::new(ty),
+                    length: None,
+                },
+                _ => ty,
+            });
+            scope.declare(param.name(), ty);
+        }
+        let locals = function
+            .syntax()
+            .descendants()
+            .filter(|node| node.kind() == SyntaxKind::LOCAL_VAR_DECL_STMT);
+        for local in locals {
+            let name = first_identifier(&local).map(|token| identifier_name(&token));
+            scope.declare(name, declared_type(&local));
+        }
+        scope
+    }
+
+    /// A name whose type is not known still hides an outer one
+    fn declare(&mut self, name: Option<String>, ty: Option<Type>) {
+        let Some(name) = name else {
+            return;
+        };
+        match ty {
+            Some(ty) => self.values.insert(name, ty),
+            None => self.values.remove(&name),
+        };
+    }
+
+    /// The type of `expr`, or `None` if it is not known
+    pub fn type_of(&self, expr: &SyntaxNode<BallerinaLanguage>) -> Option<Type> {
+        match expr.kind() {
+            SyntaxKind::PRIMARY_EXPR => {
+                // A parenthesized expression has the type of the one inside
+                if let Some(inner) = expr.children().next() {
+                    return self.type_of(&inner);
+                }
+                let token = operator(expr)?;
+                match token.kind() {
+                    SyntaxKind::IDENTIFIER => self.values.get(&identifier_name(&token)).cloned(),
+                    kind => Type::of_literal(kind, token.text()),
+                }
+            }
+            // A qualified call has no identifier directly in it
+            SyntaxKind::FUNCTION_CALL_EXPR => {
+                let callee = first_identifier(expr)?;
+                self.functions.get(&identifier_name(&callee)).cloned()
+            }
+            SyntaxKind::UNARY_EXPR => match operator(expr)?.kind() {
+                SyntaxKind::BANG => Some(Type::Boolean),
+                SyntaxKind::TILDE => Some(Type::Int),
+                _ => self.type_of(&expr.children().next()?),
+            },
+            SyntaxKind::LOGICAL_EXPR | SyntaxKind::EQUALITY_EXPR | SyntaxKind::RELATIONAL_EXPR => {
+                Some(Type::Boolean)
+            }
+            SyntaxKind::BITWISE_EXPR | SyntaxKind::SHIFT_EXPR => Some(Type::Int),
+            // Arithmetic keeps the type of operands that agree
+            SyntaxKind::ADDITIVE_EXPR | SyntaxKind::MULTIPLICATIVE_EXPR => {
+                let mut operands = expr.children();
+                let lhs = self.type_of(&operands.next()?)?;
+                let rhs = self.type_of(&operands.next()?)?;
+                (lhs == rhs).then_some(lhs)
+            }
+            SyntaxKind::CONDITIONAL_EXPR => {
+                let mut operands = expr.children().skip(1);
+                let then = self.type_of(&operands.next()?)?;
+                let otherwise = self.type_of(&operands.next()?)?;
+                (then == otherwise).then_some(then)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The first token directly in `expr`: the operator of a unary, binary or
+/// conditional expression, or the only token of a simple primary one
+pub fn operator(expr: &SyntaxNode<BallerinaLanguage>) -> Option<SyntaxToken<BallerinaLanguage>> {
+    expr.children_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .find(|token| !token.kind().is_trivia())
+}
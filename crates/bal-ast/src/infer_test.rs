@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod tests {
+    use crate::infer::Scope;
+    use crate::types::Type;
+    use crate::SourceFile;
+    use bal_parser::Parser;
+    use bal_syntax::source::FileId;
+    use bal_syntax::SyntaxKind;
+    use rowan::SyntaxNode;
+
+    /// The type of the initializer of `x` in `main`, after `module`
+    fn type_of(module: &str, expr: &str) -> Option<Type> {
+        let input = format!("{}\nfunction main(int n) {{ var x = {}; }}", module, expr);
+        let tokens = bal_syntax::lexer::lex(FileId::default(), &input).tokens;
+        let (green, _) = Parser::new(FileId::default(), &input, tokens).parse();
+        let source_file = SourceFile::new(SyntaxNode::new_root(green));
+        let main = source_file.functions().last()?;
+        let scope = Scope::module(&source_file).function(&main);
+        let decl = main
+            .syntax()
+            .descendants()
+            .find(|node| node.kind() == SyntaxKind::LOCAL_VAR_DECL_STMT)?;
+        scope.type_of(&decl.last_child()?)
+    }
+
+    #[test]
+    fn test_operator_result_types() {
+        assert_eq!(type_of("", "n >>> 2"), Some(Type::Int));
+        assert_eq!(type_of("", "~n"), Some(Type::Int));
+        assert_eq!(type_of("", "n !== 2 || false"), Some(Type::Boolean));
+        assert_eq!(type_of("", "(n + 1) * 2"), Some(Type::Int));
+        assert_eq!(type_of("", "n + 1.5"), None);
+        assert_eq!(type_of("", "()"), Some(Type::NIL));
+    }
+
+    #[test]
+    fn test_names_and_calls() {
+        let module = "const PI = 3.14;\nstring greeting = \"hi\";\n\
+                      function half(float f) returns float { return f / 2.0; }";
+        assert_eq!(type_of(module, "PI"), Some(Type::Float));
+        assert_eq!(
+            type_of(module, "n > 0 ? greeting : \"\""),
+            Some(Type::String)
+        );
+        assert_eq!(type_of(module, "half(PI)"), Some(Type::Float));
+        assert_eq!(type_of(module, "unknown"), None);
+        // A parameter hides a module-level name
+        assert_eq!(type_of("float n = 1.0;", "n"), Some(Type::Int));
+    }
+}
@@ -0,0 +1,188 @@
+//! The order module-level constants and variables are initialized in
+//!
+//! An initializer may use other module-level values, either directly or
+//! inside a function it calls, and those are initialized first. Otherwise
+//! values keep their source order.
+
+use super::{identifier_name, SourceFile};
+use bal_syntax::{BallerinaLanguage, SyntaxKind};
+use rowan::{NodeOrToken, SyntaxNode, SyntaxToken};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Default)]
+pub struct InitOrder {
+    /// Names of constants and module variables, each after everything its
+    /// initializer uses
+    pub order: Vec<String>,
+    /// Initializers that end up using their own value, each as the name
+    /// tokens around the cycle, starting with a constant or variable
+    pub cycles: Vec<Vec<SyntaxToken<BallerinaLanguage>>>,
+}
+
+/// A module-level definition and the module-level names it uses
+struct Member {
+    name: SyntaxToken<BallerinaLanguage>,
+    /// A constant or variable, rather than a function
+    is_value: bool,
+    uses: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Unvisited,
+    Active,
+    Done,
+}
+
+pub fn init_order(source_file: &SourceFile) -> InitOrder {
+    let mut members = Vec::new();
+    for decl in source_file.consts() {
+        if let Some(name) = decl.name_token() {
+            let uses = decl.initializer().map(|init| uses(&init, &HashSet::new()));
+            members.push(Member {
+                name,
+                is_value: true,
+                uses: uses.unwrap_or_default(),
+            });
+        }
+    }
+    for var in source_file.module_vars() {
+        if let Some(name) = var.name_token() {
+            let uses = var.initializer().map(|init| uses(&init, &HashSet::new()));
+            members.push(Member {
+                name,
+                is_value: true,
+                uses: uses.unwrap_or_default(),
+            });
+        }
+    }
+    for function in source_file.functions() {
+        if let Some(name) = function.name_token() {
+            let locals = local_names(function.syntax());
+            members.push(Member {
+                name,
+                is_value: false,
+                uses: uses(function.syntax(), &locals),
+            });
+        }
+    }
+    // Consts and variables were collected by kind; restore source order
+    members.sort_by_key(|member| member.name.text_range().start());
+
+    // A duplicate name refers to its first definition
+    let mut index = HashMap::new();
+    for (i, member) in members.iter().enumerate() {
+        index.entry(identifier_name(&member.name)).or_insert(i);
+    }
+
+    let mut visit = Visit {
+        members: &members,
+        index: &index,
+        state: vec![State::Unvisited; members.len()],
+        stack: Vec::new(),
+        result: InitOrder::default(),
+    };
+    for (i, member) in members.iter().enumerate() {
+        if member.is_value && visit.state[i] == State::Unvisited {
+            visit.visit(i);
+        }
+    }
+    visit.result
+}
+
+/// A depth-first search through uses, emitting each value after its uses
+struct Visit<'a> {
+    members: &'a [Member],
+    index: &'a HashMap<String, usize>,
+    state: Vec<State>,
+    stack: Vec<usize>,
+    result: InitOrder,
+}
+
+impl Visit<'_> {
+    fn visit(&mut self, i: usize) {
+        self.state[i] = State::Active;
+        self.stack.push(i);
+        for name in &self.members[i].uses {
+            let Some(&used) = self.index.get(name) else {
+                continue;
+            };
+            match self.state[used] {
+                State::Unvisited => self.visit(used),
+                State::Active => self.record_cycle(used),
+                State::Done => {}
+            }
+        }
+        self.stack.pop();
+        self.state[i] = State::Done;
+        if self.members[i].is_value {
+            self.result
+                .order
+                .push(identifier_name(&self.members[i].name));
+        }
+    }
+
+    /// Record the cycle from `used` to the top of the stack, unless it only
+    /// goes through functions, which is plain recursion
+    fn record_cycle(&mut self, used: usize) {
+        let start = self
+            .stack
+            .iter()
+            .position(|&i| i == used)
+            .expect("an active member is on the stack");
+        let mut cycle = self.stack[start..].to_vec();
+        let Some(first_value) = cycle.iter().position(|&i| self.members[i].is_value) else {
+            return;
+        };
+        cycle.rotate_left(first_value);
+        self.result.cycles.push(
+            cycle
+                .into_iter()
+                .map(|i| self.members[i].name.clone())
+                .collect(),
+        );
+    }
+}
+
+/// The names of identifiers used in `node`, other than `locals`, the names
+/// after a module prefix, the names of record fields and the parameter
+/// names of named arguments
+fn uses(node: &SyntaxNode<BallerinaLanguage>, locals: &HashSet<String>) -> Vec<String> {
+    node.descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .filter(|token| token.kind() == SyntaxKind::IDENTIFIER)
+        .filter(|token| {
+            token.parent().is_some_and(|parent| {
+                !matches!(
+                    parent.kind(),
+                    SyntaxKind::QUALIFIED_IDENTIFIER
+                        | SyntaxKind::RECORD_FIELD
+                        | SyntaxKind::NAMED_ARG
+                )
+            })
+        })
+        .map(|token| identifier_name(&token))
+        .filter(|name| !locals.contains(name))
+        .collect()
+}
+
+/// The function's own name with its parameters and local variables, which
+/// hide module-level names of the same spelling
+fn local_names(function: &SyntaxNode<BallerinaLanguage>) -> HashSet<String> {
+    function
+        .descendants()
+        .filter(|node| {
+            matches!(
+                node.kind(),
+                SyntaxKind::FUNCTION_DEF
+                    | SyntaxKind::PARAM
+                    | SyntaxKind::DEFAULTABLE_PARAM
+                    | SyntaxKind::REST_PARAM
+                    | SyntaxKind::INCLUDED_RECORD_PARAM
+                    | SyntaxKind::LOCAL_VAR_DECL_STMT
+            )
+        })
+        .filter_map(|node| super::first_identifier(&node))
+        .map(|token| identifier_name(&token))
+        .collect()
+}
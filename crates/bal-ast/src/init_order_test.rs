@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests {
+    use crate::init_order::init_order;
+    use crate::{identifier_name, SourceFile};
+    use bal_parser::Parser;
+    use bal_syntax::source::FileId;
+    use rowan::SyntaxNode;
+
+    fn parse(input: &str) -> SourceFile {
+        let tokens = bal_syntax::lexer::lex(FileId::default(), input).tokens;
+        let (green, _) = Parser::new(FileId::default(), input, tokens).parse();
+        SourceFile::new(SyntaxNode::new_root(green))
+    }
+
+    fn order(input: &str) -> Vec<String> {
+        init_order(&parse(input)).order
+    }
+
+    /// Each cycle as its names joined by ` -> `
+    fn cycles(input: &str) -> Vec<String> {
+        init_order(&parse(input))
+            .cycles
+            .iter()
+            .map(|cycle| {
+                cycle
+                    .iter()
+                    .map(identifier_name)
+                    .collect::<Vec<_>>()
+                    .join(" -> ")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_independent_values_keep_source_order() {
+        assert_eq!(
+            order("const A = 1;\nint b = 2;\nfinal int c = 3;"),
+            ["A", "b", "c"]
+        );
+    }
+
+    #[test]
+    fn test_uses_come_first() {
+        assert_eq!(
+            order("int total = base * SCALE;\nint base = 2;\nconst SCALE = 10;"),
+            ["base", "SCALE", "total"]
+        );
+    }
+
+    #[test]
+    fn test_uses_through_functions_come_first() {
+        let input = "int total = sum();\n\
+                     function sum() returns int { return base + 1; }\n\
+                     int base = 2;";
+        assert_eq!(order(input), ["base", "total"]);
+        assert!(cycles(input).is_empty());
+    }
+
+    #[test]
+    fn test_locals_hide_module_values() {
+        let input = "int count = f(1);\n\
+                     function f(int count) returns int { int total = count; return total; }\n\
+                     int total = 0;";
+        assert_eq!(order(input), ["count", "total"]);
+        assert!(cycles(input).is_empty());
+    }
+
+    #[test]
+    fn test_recursive_functions_are_not_cycles() {
+        let input = "int x = f(3);\nfunction f(int n) returns int { return f(n - 1); }";
+        assert!(cycles(input).is_empty());
+    }
+
+    #[test]
+    fn test_cycles() {
+        assert_eq!(cycles("int x = x + 1;"), ["x"]);
+        assert_eq!(cycles("const A = B;\nconst B = A;"), ["A -> B"]);
+        assert_eq!(
+            cycles("function f() returns int { return x; }\nint x = f();"),
+            ["x -> f"]
+        );
+    }
+
+    #[test]
+    fn test_qualified_names_are_not_module_values() {
+        assert!(cycles("import ballerina/io;\nint x = io:x();").is_empty());
+    }
+}
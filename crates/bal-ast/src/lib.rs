@@ -2,7 +2,13 @@
 //! Handles AST processing and semantic analysis
 
 use bal_syntax::SyntaxKind;
-use rowan::SyntaxNode;
+use rowan::{SyntaxNode, SyntaxToken};
+
+/// The name an identifier token stands for, used for all name comparisons.
+/// `'foo` and `foo` have the same name; invalid escapes are kept verbatim.
+pub fn identifier_name(token: &SyntaxToken<bal_syntax::BallerinaLanguage>) -> String {
+    bal_syntax::literal::identifier_value(token.text()).unwrap_or_else(|_| token.text().to_string())
+}
 
 #[derive(Debug)]
 pub struct SourceFile {
@@ -14,10 +20,102 @@ impl SourceFile {
         Self { syntax }
     }
 
+    pub fn syntax(&self) -> &SyntaxNode<bal_syntax::BallerinaLanguage> {
+        &self.syntax
+    }
+
     pub fn functions(&self) -> impl Iterator<Item = Function> {
-        self.syntax
-            .children()
-            .filter_map(|node| Function::cast(node))
+        self.syntax.children().filter_map(Function::cast)
+    }
+
+    pub fn imports(&self) -> impl Iterator<Item = Import> {
+        self.syntax.children().filter_map(Import::cast)
+    }
+
+    pub fn consts(&self) -> impl Iterator<Item = ConstDecl> {
+        self.syntax.children().filter_map(ConstDecl::cast)
+    }
+
+    pub fn module_vars(&self) -> impl Iterator<Item = ModuleVar> {
+        self.syntax.children().filter_map(ModuleVar::cast)
+    }
+
+    pub fn type_definitions(&self) -> impl Iterator<Item = TypeDefinition> {
+        self.syntax.children().filter_map(TypeDefinition::cast)
+    }
+}
+
+#[derive(Debug)]
+pub struct Import {
+    syntax: SyntaxNode<bal_syntax::BallerinaLanguage>,
+}
+
+impl Import {
+    pub fn cast(node: SyntaxNode<bal_syntax::BallerinaLanguage>) -> Option<Self> {
+        if node.kind() == SyntaxKind::IMPORT_DECL {
+            Some(Self { syntax: node })
+        } else {
+            None
+        }
+    }
+
+    pub fn syntax(&self) -> &SyntaxNode<bal_syntax::BallerinaLanguage> {
+        &self.syntax
+    }
+
+    /// The organization, as `ballerina` in `import ballerina/io;`. Modules
+    /// of the current package are imported without one.
+    pub fn org(&self) -> Option<String> {
+        self.part(SyntaxKind::IMPORT_ORG_NAME)?
+            .children_with_tokens()
+            .filter_map(|element| element.into_token())
+            .find(|token| token.kind() == SyntaxKind::IDENTIFIER)
+            .map(|token| identifier_name(&token))
+    }
+
+    /// The normalized components of the module name: `lang` and `int` in
+    /// `import ballerina/lang.'int;`
+    pub fn module_name(&self) -> Vec<String> {
+        self.module_name_tokens()
+            .map(|token| identifier_name(&token))
+            .collect()
+    }
+
+    /// The identifier the module is referred to by: the one after `as`, or
+    /// else the last component of its name, as `io` in `import ballerina/io;`
+    pub fn prefix_token(&self) -> Option<SyntaxToken<bal_syntax::BallerinaLanguage>> {
+        match self.part(SyntaxKind::IMPORT_PREFIX) {
+            Some(prefix) => prefix
+                .children_with_tokens()
+                .filter_map(|element| element.into_token())
+                .find(|token| token.kind() == SyntaxKind::IDENTIFIER),
+            None => self.module_name_tokens().last(),
+        }
+    }
+
+    /// The normalized prefix, if the import got that far
+    pub fn prefix(&self) -> Option<String> {
+        self.prefix_token().map(|token| identifier_name(&token))
+    }
+
+    /// Whether the module is imported as `_`, only for its initialization,
+    /// so its prefix cannot be used
+    pub fn is_anonymous(&self) -> bool {
+        self.part(SyntaxKind::IMPORT_PREFIX).is_some() && self.prefix().as_deref() == Some("_")
+    }
+
+    fn part(&self, kind: SyntaxKind) -> Option<SyntaxNode<bal_syntax::BallerinaLanguage>> {
+        self.syntax.children().find(|node| node.kind() == kind)
+    }
+
+    fn module_name_tokens(
+        &self,
+    ) -> impl Iterator<Item = SyntaxToken<bal_syntax::BallerinaLanguage>> {
+        self.part(SyntaxKind::MODULE_NAME)
+            .into_iter()
+            .flat_map(|name| name.children_with_tokens())
+            .filter_map(|element| element.into_token())
+            .filter(|token| token.kind() == SyntaxKind::IDENTIFIER)
     }
 }
 
@@ -34,8 +132,287 @@ impl Function {
             None
         }
     }
+
+    pub fn syntax(&self) -> &SyntaxNode<bal_syntax::BallerinaLanguage> {
+        &self.syntax
+    }
+
+    /// The normalized function name, if the definition got that far
+    pub fn name(&self) -> Option<String> {
+        self.name_token().map(|token| identifier_name(&token))
+    }
+
+    /// The identifier token naming the function, as written
+    pub fn name_token(&self) -> Option<SyntaxToken<bal_syntax::BallerinaLanguage>> {
+        self.syntax
+            .children_with_tokens()
+            .filter_map(|element| element.into_token())
+            .find(|token| token.kind() == SyntaxKind::IDENTIFIER)
+    }
+
+    /// The `#` documentation written directly before the function
+    pub fn docs(&self) -> Option<docs::Documentation> {
+        docs::Documentation::of_node(&self.syntax)
+    }
+
+    /// The declared return type; `()` when there is no `returns` clause
+    pub fn return_type(&self) -> Option<types::Type> {
+        let signature = self
+            .syntax
+            .children()
+            .find(|node| node.kind() == SyntaxKind::SIGNATURE)?;
+        match signature
+            .children()
+            .find(|child| child.kind().is_type_desc())
+        {
+            Some(desc) => types::Type::of_type_desc(&desc),
+            None => Some(types::Type::NIL),
+        }
+    }
+
+    /// The parameters of every kind, in order
+    pub fn params(&self) -> impl Iterator<Item = Param> {
+        self.syntax
+            .children()
+            .filter(|node| node.kind() == SyntaxKind::SIGNATURE)
+            .flat_map(|signature| signature.children())
+            .filter(|node| node.kind() == SyntaxKind::PARAM_LIST)
+            .flat_map(|list| list.children())
+            .filter_map(Param::cast)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamKind {
+    /// `int x`
+    Required,
+    /// `int x = 10`
+    Defaultable,
+    /// `int... xs`
+    Rest,
+    /// `*Options opts`
+    IncludedRecord,
 }
 
+/// A function parameter of any kind
+#[derive(Debug)]
+pub struct Param {
+    syntax: SyntaxNode<bal_syntax::BallerinaLanguage>,
+}
+
+impl Param {
+    pub fn cast(node: SyntaxNode<bal_syntax::BallerinaLanguage>) -> Option<Self> {
+        match node.kind() {
+            SyntaxKind::PARAM
+            | SyntaxKind::DEFAULTABLE_PARAM
+            | SyntaxKind::REST_PARAM
+            | SyntaxKind::INCLUDED_RECORD_PARAM => Some(Self { syntax: node }),
+            _ => None,
+        }
+    }
+
+    pub fn syntax(&self) -> &SyntaxNode<bal_syntax::BallerinaLanguage> {
+        &self.syntax
+    }
+
+    pub fn kind(&self) -> ParamKind {
+        match self.syntax.kind() {
+            SyntaxKind::DEFAULTABLE_PARAM => ParamKind::Defaultable,
+            SyntaxKind::REST_PARAM => ParamKind::Rest,
+            SyntaxKind::INCLUDED_RECORD_PARAM => ParamKind::IncludedRecord,
+            _ => ParamKind::Required,
+        }
+    }
+
+    /// The normalized parameter name, if the parameter got that far
+    pub fn name(&self) -> Option<String> {
+        self.name_token().map(|token| identifier_name(&token))
+    }
+
+    pub fn name_token(&self) -> Option<SyntaxToken<bal_syntax::BallerinaLanguage>> {
+        first_identifier(&self.syntax)
+    }
+
+    /// The type as written; for a rest parameter, that of each argument
+    pub fn declared_type(&self) -> Option<types::Type> {
+        declared_type(&self.syntax)
+    }
+
+    pub fn type_desc(&self) -> Option<SyntaxNode<bal_syntax::BallerinaLanguage>> {
+        self.syntax
+            .children()
+            .find(|child| child.kind().is_type_desc())
+    }
+
+    /// The default value of a defaultable parameter
+    pub fn default(&self) -> Option<SyntaxNode<bal_syntax::BallerinaLanguage>> {
+        initializer(&self.syntax)
+    }
+}
+
+/// `const [type-desc] name = expr;`
+#[derive(Debug)]
+pub struct ConstDecl {
+    syntax: SyntaxNode<bal_syntax::BallerinaLanguage>,
+}
+
+impl ConstDecl {
+    pub fn cast(node: SyntaxNode<bal_syntax::BallerinaLanguage>) -> Option<Self> {
+        if node.kind() == SyntaxKind::CONST_DECL {
+            Some(Self { syntax: node })
+        } else {
+            None
+        }
+    }
+
+    pub fn syntax(&self) -> &SyntaxNode<bal_syntax::BallerinaLanguage> {
+        &self.syntax
+    }
+
+    /// The normalized constant name, if the declaration got that far
+    pub fn name(&self) -> Option<String> {
+        self.name_token().map(|token| identifier_name(&token))
+    }
+
+    pub fn name_token(&self) -> Option<SyntaxToken<bal_syntax::BallerinaLanguage>> {
+        first_identifier(&self.syntax)
+    }
+
+    /// The declared type; without one, the type is that of the value
+    pub fn declared_type(&self) -> Option<types::Type> {
+        declared_type(&self.syntax)
+    }
+
+    pub fn initializer(&self) -> Option<SyntaxNode<bal_syntax::BallerinaLanguage>> {
+        initializer(&self.syntax)
+    }
+}
+
+/// `[final | configurable] type-desc name = expr;` at module level
+#[derive(Debug)]
+pub struct ModuleVar {
+    syntax: SyntaxNode<bal_syntax::BallerinaLanguage>,
+}
+
+impl ModuleVar {
+    pub fn cast(node: SyntaxNode<bal_syntax::BallerinaLanguage>) -> Option<Self> {
+        if node.kind() == SyntaxKind::MODULE_VAR_DECL {
+            Some(Self { syntax: node })
+        } else {
+            None
+        }
+    }
+
+    pub fn syntax(&self) -> &SyntaxNode<bal_syntax::BallerinaLanguage> {
+        &self.syntax
+    }
+
+    /// The normalized variable name, if the declaration got that far
+    pub fn name(&self) -> Option<String> {
+        self.name_token().map(|token| identifier_name(&token))
+    }
+
+    pub fn name_token(&self) -> Option<SyntaxToken<bal_syntax::BallerinaLanguage>> {
+        first_identifier(&self.syntax)
+    }
+
+    pub fn declared_type(&self) -> Option<types::Type> {
+        declared_type(&self.syntax)
+    }
+
+    pub fn is_final(&self) -> bool {
+        self.has_token(SyntaxKind::FINAL_KW)
+    }
+
+    /// Configurable variables are implicitly final
+    pub fn is_configurable(&self) -> bool {
+        self.has_token(SyntaxKind::CONFIGURABLE_KW)
+    }
+
+    /// The default value; `None` for a configurable variable written
+    /// `= ?`, whose value must be supplied by configuration
+    pub fn initializer(&self) -> Option<SyntaxNode<bal_syntax::BallerinaLanguage>> {
+        initializer(&self.syntax)
+    }
+
+    fn has_token(&self, kind: SyntaxKind) -> bool {
+        self.syntax
+            .children_with_tokens()
+            .filter_map(|element| element.into_token())
+            .any(|token| token.kind() == kind)
+    }
+}
+
+/// `type Name type-desc;`
+#[derive(Debug)]
+pub struct TypeDefinition {
+    syntax: SyntaxNode<bal_syntax::BallerinaLanguage>,
+}
+
+impl TypeDefinition {
+    pub fn cast(node: SyntaxNode<bal_syntax::BallerinaLanguage>) -> Option<Self> {
+        if node.kind() == SyntaxKind::TYPE_DEFINITION {
+            Some(Self { syntax: node })
+        } else {
+            None
+        }
+    }
+
+    pub fn syntax(&self) -> &SyntaxNode<bal_syntax::BallerinaLanguage> {
+        &self.syntax
+    }
+
+    /// The normalized type name, if the definition got that far
+    pub fn name(&self) -> Option<String> {
+        self.name_token().map(|token| identifier_name(&token))
+    }
+
+    pub fn name_token(&self) -> Option<SyntaxToken<bal_syntax::BallerinaLanguage>> {
+        first_identifier(&self.syntax)
+    }
+
+    /// The type the name stands for
+    pub fn declared_type(&self) -> Option<types::Type> {
+        declared_type(&self.syntax)
+    }
+}
+
+/// The first identifier directly in `node`, which names a declaration
+fn first_identifier(
+    node: &SyntaxNode<bal_syntax::BallerinaLanguage>,
+) -> Option<SyntaxToken<bal_syntax::BallerinaLanguage>> {
+    node.children_with_tokens()
+        .filter_map(|element| element.into_token())
+        .find(|token| token.kind() == SyntaxKind::IDENTIFIER)
+}
+
+fn declared_type(node: &SyntaxNode<bal_syntax::BallerinaLanguage>) -> Option<types::Type> {
+    let desc = node.children().find(|child| child.kind().is_type_desc())?;
+    types::Type::of_type_desc(&desc)
+}
+
+/// The expression after `=`: the only child node other than the type
+fn initializer(
+    node: &SyntaxNode<bal_syntax::BallerinaLanguage>,
+) -> Option<SyntaxNode<bal_syntax::BallerinaLanguage>> {
+    node.children()
+        .find(|child| !child.kind().is_type_desc() && child.kind() != SyntaxKind::ERROR)
+}
+
+pub mod docs;
+pub mod infer;
+pub mod init_order;
 pub mod semantic;
 pub mod types;
 pub mod validation;
+
+#[cfg(test)]
+mod docs_test;
+#[cfg(test)]
+mod infer_test;
+#[cfg(test)]
+mod init_order_test;
+#[cfg(test)]
+mod semantic_test;
+#[cfg(test)]
+mod types_test;
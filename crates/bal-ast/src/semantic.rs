@@ -1,18 +1,607 @@
 //! Semantic analysis implementation
 
-use super::SourceFile;
+use super::infer::{operator, Scope};
+use super::init_order::init_order;
+use super::types::{RecordField, Type};
+use super::{Function, Param, ParamKind, SourceFile};
+use bal_syntax::diagnostic::{codes, Diagnostic, Fix};
+use bal_syntax::error::{LexerError, Span};
+use bal_syntax::literal;
+use bal_syntax::source::FileId;
+use bal_syntax::text_edit::TextEdit;
+use bal_syntax::{BallerinaLanguage, SyntaxKind};
+use rowan::{Direction, NodeOrToken, SyntaxNode, SyntaxToken, TextRange, TextSize};
+use std::collections::{HashMap, HashSet};
 
 pub struct SemanticAnalyzer {
+    file: FileId,
     source_file: SourceFile,
 }
 
 impl SemanticAnalyzer {
-    pub fn new(source_file: SourceFile) -> Self {
-        Self { source_file }
+    pub fn new(file: FileId, source_file: SourceFile) -> Self {
+        Self { file, source_file }
     }
 
-    pub fn analyze(&self) -> Result<(), Vec<String>> {
-        // Implement semantic analysis
-        Ok(())
+    pub fn analyze(&self) -> Result<(), Vec<Diagnostic>> {
+        let mut diagnostics = Vec::new();
+        self.check_duplicate_definitions(&mut diagnostics);
+        self.check_unused_imports(&mut diagnostics);
+        self.check_cyclic_initializers(&mut diagnostics);
+        self.check_call_arguments(&mut diagnostics);
+        self.check_int_min_literals(&mut diagnostics);
+        self.check_operator_types(&mut diagnostics);
+
+        if diagnostics.is_empty() {
+            Ok(())
+        } else {
+            Err(diagnostics)
+        }
+    }
+
+    /// Functions, constants, variables and types share one module namespace;
+    /// `'f` and `f` are the same name
+    fn check_duplicate_definitions(&self, diagnostics: &mut Vec<Diagnostic>) {
+        let source_file = &self.source_file;
+        let mut definitions: Vec<_> = source_file
+            .functions()
+            .filter_map(|f| f.name_token().map(|token| (token, "function")))
+            .chain(
+                source_file
+                    .consts()
+                    .filter_map(|c| c.name_token().map(|token| (token, "constant"))),
+            )
+            .chain(
+                source_file
+                    .module_vars()
+                    .filter_map(|v| v.name_token().map(|token| (token, "variable"))),
+            )
+            .chain(
+                source_file
+                    .type_definitions()
+                    .filter_map(|t| t.name_token().map(|token| (token, "type"))),
+            )
+            .collect();
+        definitions.sort_by_key(|(token, _)| token.text_range().start());
+
+        let mut seen = HashMap::new();
+        for (token, kind) in definitions {
+            let name = crate::identifier_name(&token);
+            let span = Span::new(self.file, token.text_range());
+            match seen.get(&name) {
+                Some(&first) => diagnostics.push(
+                    Diagnostic::error(
+                        codes::DUPLICATE_DEFINITION,
+                        format!("duplicate definition of {} '{}'", kind, name),
+                    )
+                    .with_primary(span, "redefined here")
+                    .with_label(first, "first defined here")
+                    .with_help("rename one of the definitions"),
+                ),
+                None => {
+                    seen.insert(name, span);
+                }
+            }
+        }
+    }
+
+    /// A constant or module variable cannot be initialized in terms of
+    /// itself, even through the functions its initializer calls
+    fn check_cyclic_initializers(&self, diagnostics: &mut Vec<Diagnostic>) {
+        for cycle in init_order(&self.source_file).cycles {
+            let name = crate::identifier_name(&cycle[0]);
+            let path: Vec<_> = cycle
+                .iter()
+                .chain(&cycle[..1])
+                .map(crate::identifier_name)
+                .collect();
+            let mut diagnostic = Diagnostic::error(
+                codes::CYCLIC_INITIALIZATION,
+                format!("initializer of '{}' depends on itself", name),
+            )
+            .with_primary(Span::new(self.file, cycle[0].text_range()), "declared here");
+            for token in &cycle[1..] {
+                diagnostic = diagnostic.with_label(
+                    Span::new(self.file, token.text_range()),
+                    format!("'{}' is part of the cycle", crate::identifier_name(token)),
+                );
+            }
+            diagnostics.push(diagnostic.with_note(format!("cycle: {}", path.join(" -> "))));
+        }
+    }
+
+    /// An import is used when its prefix qualifies a name, as in `io:println`
+    fn check_unused_imports(&self, diagnostics: &mut Vec<Diagnostic>) {
+        let root = self.source_file.syntax();
+        let used: HashSet<String> = root
+            .descendants_with_tokens()
+            .filter_map(NodeOrToken::into_token)
+            .filter(|token| token.kind() == SyntaxKind::IDENTIFIER)
+            .filter(|token| {
+                !token
+                    .parent_ancestors()
+                    .any(|node| node.kind() == SyntaxKind::IMPORT_DECL)
+            })
+            .filter(|token| {
+                next_non_trivia(token).is_some_and(|next| next.kind() == SyntaxKind::COLON)
+            })
+            .map(|token| crate::identifier_name(&token))
+            .collect();
+
+        for import in self.source_file.imports() {
+            if import.is_anonymous() {
+                continue;
+            }
+            let Some(prefix) = import.prefix_token() else {
+                continue;
+            };
+            let name = crate::identifier_name(&prefix);
+            if used.contains(&name) {
+                continue;
+            }
+            let node = import.syntax();
+            let range = node_range(node);
+            let mut end = range.end();
+            // Take the line the import was on with it
+            if let Some(newline) = node
+                .last_token()
+                .and_then(|token| token.next_token())
+                .filter(|token| token.kind() == SyntaxKind::NEWLINE)
+            {
+                let text = newline.text();
+                let len = if text.starts_with("\r\n") { 2 } else { 1 };
+                end = newline.text_range().start() + TextSize::from(len);
+            }
+
+            diagnostics.push(
+                Diagnostic::warning(codes::UNUSED_IMPORT, format!("unused import '{}'", name))
+                    .with_primary(Span::new(self.file, range), "imported here")
+                    .with_fix(Fix::new(
+                        "remove the unused import",
+                        self.file,
+                        vec![TextEdit::delete(TextRange::new(range.start(), end))],
+                    )),
+            );
+        }
+    }
+
+    /// Each call to a function of this module gives every required
+    /// parameter one argument, by position or by name, and literal arguments
+    /// have their parameter's type. Calls into other modules are not checked.
+    fn check_call_arguments(&self, diagnostics: &mut Vec<Diagnostic>) {
+        let mut functions = HashMap::new();
+        for function in self.source_file.functions() {
+            if let Some(name) = function.name() {
+                functions.entry(name).or_insert(function);
+            }
+        }
+
+        let calls = self
+            .source_file
+            .syntax()
+            .descendants()
+            .filter(|node| node.kind() == SyntaxKind::FUNCTION_CALL_EXPR);
+        for call in calls {
+            // A qualified callee is in a QUALIFIED_IDENTIFIER node instead
+            let Some(callee) = crate::first_identifier(&call) else {
+                continue;
+            };
+            let Some(function) = functions.get(&crate::identifier_name(&callee)) else {
+                continue;
+            };
+            let Some(args) = call
+                .children()
+                .find(|node| node.kind() == SyntaxKind::ARG_LIST)
+            else {
+                continue;
+            };
+            // An argument list that did not parse has already been reported
+            let complete = args
+                .children_with_tokens()
+                .any(|element| element.kind() == SyntaxKind::R_PAREN);
+            if complete {
+                self.check_call(function, &callee, &args, diagnostics);
+            }
+        }
+    }
+
+    fn check_call(
+        &self,
+        function: &Function,
+        callee: &SyntaxToken<BallerinaLanguage>,
+        args: &SyntaxNode<BallerinaLanguage>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let name = crate::identifier_name(callee);
+        let call_span = Span::new(self.file, callee.text_range());
+        let (rest, params): (Vec<_>, Vec<_>) = function
+            .params()
+            .partition(|param| param.kind() == ParamKind::Rest);
+        let defined = function
+            .name_token()
+            .map(|token| Span::new(self.file, token.text_range()))
+            .unwrap_or(call_span);
+        // The fields of included record parameters, which named arguments
+        // may also give; `None` if one of their types is not known here
+        let fields: Option<Vec<_>> = params
+            .iter()
+            .filter(|param| param.kind() == ParamKind::IncludedRecord)
+            .map(|param| self.included_fields(param))
+            .collect::<Option<Vec<_>>>()
+            .map(|fields| fields.into_iter().flatten().collect());
+        // The parameters and fields given so far, and where
+        let mut given = HashMap::new();
+
+        let positional = args
+            .children()
+            .filter(|arg| arg.kind() != SyntaxKind::NAMED_ARG);
+        for (i, arg) in positional.enumerate() {
+            let span = Span::new(self.file, node_range(&arg));
+            match params.get(i).or(rest.first()) {
+                Some(param) => {
+                    if param.kind() != ParamKind::Rest {
+                        if let Some(param_name) = param.name() {
+                            given.insert(param_name, span);
+                        }
+                    }
+                    self.check_argument_type(param, &arg, diagnostics);
+                }
+                None => {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            codes::INVALID_ARGUMENTS,
+                            format!("too many arguments in call to '{}'", name),
+                        )
+                        .with_primary(span, "unexpected argument")
+                        .with_label(defined, {
+                            let all_required = params
+                                .iter()
+                                .all(|param| param.kind() == ParamKind::Required);
+                            let bound = if all_required { "" } else { "at most " };
+                            format!("'{}' takes {}{} arguments", name, bound, params.len())
+                        }),
+                    );
+                    break;
+                }
+            }
+        }
+
+        let named = args
+            .children()
+            .filter(|arg| arg.kind() == SyntaxKind::NAMED_ARG);
+        for arg in named {
+            let Some(token) = crate::first_identifier(&arg) else {
+                continue;
+            };
+            let arg_name = crate::identifier_name(&token);
+            let span = Span::new(self.file, token.text_range());
+            let param = params
+                .iter()
+                .find(|param| param.name().as_deref() == Some(arg_name.as_str()));
+            let is_field = fields
+                .as_ref()
+                .is_none_or(|fields| fields.iter().any(|field| field.name == arg_name));
+            if param.is_none() && !is_field {
+                diagnostics.push(
+                    Diagnostic::error(
+                        codes::INVALID_ARGUMENTS,
+                        format!("'{}' has no parameter named '{}'", name, arg_name),
+                    )
+                    .with_primary(span, "unknown parameter")
+                    .with_label(defined, "defined here"),
+                );
+                continue;
+            }
+            if let Some(&first) = given.get(&arg_name) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        codes::INVALID_ARGUMENTS,
+                        format!("argument for '{}' is given more than once", arg_name),
+                    )
+                    .with_primary(span, "given again here")
+                    .with_label(first, "first given here"),
+                );
+                continue;
+            }
+            given.insert(arg_name, span);
+            if let (Some(param), Some(value)) = (param, arg.children().next()) {
+                self.check_argument_type(param, &value, diagnostics);
+            }
+        }
+
+        for param in &params {
+            let (Some(param_name), Some(token)) = (param.name(), param.name_token()) else {
+                continue;
+            };
+            if given.contains_key(&param_name) {
+                continue;
+            }
+            match param.kind() {
+                ParamKind::Required => diagnostics.push(
+                    Diagnostic::error(
+                        codes::INVALID_ARGUMENTS,
+                        format!(
+                            "missing argument for parameter '{}' in call to '{}'",
+                            param_name, name
+                        ),
+                    )
+                    .with_primary(call_span, format!("'{}' is not given", param_name))
+                    .with_label(
+                        Span::new(self.file, token.text_range()),
+                        "parameter declared here",
+                    ),
+                ),
+                // Named arguments for the fields make up the record
+                ParamKind::IncludedRecord => {
+                    let required = self
+                        .included_fields(param)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter(|field| field.is_required() && !given.contains_key(&field.name));
+                    for field in required {
+                        diagnostics.push(
+                            Diagnostic::error(
+                                codes::INVALID_ARGUMENTS,
+                                format!(
+                                    "missing argument for field '{}' of '{}' in call to '{}'",
+                                    field.name, param_name, name
+                                ),
+                            )
+                            .with_primary(call_span, format!("'{}' is not given", field.name))
+                            .with_help(format!(
+                                "pass '{}' by name, or pass '{}' itself",
+                                field.name, param_name
+                            )),
+                        );
+                    }
+                }
+                ParamKind::Defaultable | ParamKind::Rest => {}
+            }
+        }
+    }
+
+    /// `9223372036854775808` is only in range when negated, as in a unary
+    /// `-` expression or a `-n` singleton type
+    fn check_int_min_literals(&self, diagnostics: &mut Vec<Diagnostic>) {
+        let literals = self
+            .source_file
+            .syntax()
+            .descendants_with_tokens()
+            .filter_map(NodeOrToken::into_token)
+            .filter(|token| {
+                token.kind() == SyntaxKind::INTEGER_LITERAL
+                    && literal::is_int_min_magnitude(token.text())
+            });
+        for token in literals {
+            if !is_negated(&token) {
+                diagnostics.push(
+                    LexerError::InvalidNumber {
+                        message: format!("int literal '{}' is out of range for int", token.text()),
+                        span: Span::new(self.file, token.text_range()),
+                    }
+                    .into(),
+                );
+            }
+        }
+    }
+
+    /// A literal argument must be a value of its parameter's type; other
+    /// expressions are not typed yet
+    fn check_argument_type(
+        &self,
+        param: &Param,
+        arg: &SyntaxNode<BallerinaLanguage>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let (Some(ty), Some(desc), Some(name_token)) =
+            (param.declared_type(), param.type_desc(), param.name_token())
+        else {
+            return;
+        };
+        let tokens: Vec<_> = arg
+            .children_with_tokens()
+            .filter_map(NodeOrToken::into_token)
+            .filter(|token| !token.kind().is_trivia())
+            .collect();
+        let literal = match tokens.as_slice() {
+            [literal] => literal,
+            [open, close] if close.kind() == SyntaxKind::R_PAREN => open,
+            _ => return,
+        };
+        if arg.kind() != SyntaxKind::PRIMARY_EXPR
+            || ty.admits_literal(literal.kind(), literal.text())
+        {
+            return;
+        }
+        let Some(found) = Type::of_literal(literal.kind(), literal.text()) else {
+            return;
+        };
+        diagnostics.push(
+            Diagnostic::error(
+                codes::ARGUMENT_TYPE_MISMATCH,
+                format!(
+                    "argument for parameter '{}' has the wrong type",
+                    crate::identifier_name(&name_token)
+                ),
+            )
+            .with_primary(
+                Span::new(self.file, node_range(arg)),
+                format!("expected '{}', found '{}'", desc.text(), found),
+            )
+            .with_label(
+                Span::new(self.file, name_token.text_range()),
+                "parameter declared here",
+            ),
+        );
+    }
+
+    /// Logical operators and the conditions of conditional expressions, `if`
+    /// and `while` take booleans, and bitwise and shift operators ints.
+    /// Operands whose type is not known are not checked.
+    fn check_operator_types(&self, diagnostics: &mut Vec<Diagnostic>) {
+        let module = Scope::module(&self.source_file);
+        for function in self.source_file.functions() {
+            let scope = module.function(&function);
+            self.check_operators_in(function.syntax(), &scope, diagnostics);
+        }
+        for decl in self.source_file.consts() {
+            self.check_operators_in(decl.syntax(), &module, diagnostics);
+        }
+        for var in self.source_file.module_vars() {
+            self.check_operators_in(var.syntax(), &module, diagnostics);
+        }
     }
+
+    fn check_operators_in(
+        &self,
+        node: &SyntaxNode<BallerinaLanguage>,
+        scope: &Scope,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        for expr in node.descendants() {
+            let Some(op) = operator(&expr) else {
+                continue;
+            };
+            let is_condition = matches!(
+                expr.kind(),
+                SyntaxKind::CONDITIONAL_EXPR | SyntaxKind::IF_ELSE_STMT | SyntaxKind::WHILE_STMT
+            );
+            let (expected, operands): (Type, Vec<_>) = match expr.kind() {
+                SyntaxKind::LOGICAL_EXPR => (Type::Boolean, expr.children().collect()),
+                SyntaxKind::BITWISE_EXPR | SyntaxKind::SHIFT_EXPR => {
+                    (Type::Int, expr.children().collect())
+                }
+                SyntaxKind::UNARY_EXPR if op.kind() == SyntaxKind::BANG => {
+                    (Type::Boolean, expr.children().collect())
+                }
+                SyntaxKind::UNARY_EXPR if op.kind() == SyntaxKind::TILDE => {
+                    (Type::Int, expr.children().collect())
+                }
+                _ if is_condition => (Type::Boolean, expr.children().take(1).collect()),
+                _ => continue,
+            };
+            for operand in operands {
+                let Some(found) = scope.type_of(&operand) else {
+                    continue;
+                };
+                match self.resolve_type(&found, &mut HashSet::new()) {
+                    Some(resolved) if resolved != expected => {}
+                    _ => continue,
+                }
+                let message = if is_condition {
+                    format!("condition has type '{}', not 'boolean'", found)
+                } else {
+                    format!("operator '{}' cannot be applied to '{}'", op.text(), found)
+                };
+                diagnostics.push(
+                    Diagnostic::error(codes::OPERATOR_TYPE_MISMATCH, message).with_primary(
+                        Span::new(self.file, node_range(&operand)),
+                        format!("expected '{}'", expected),
+                    ),
+                );
+            }
+        }
+    }
+
+    /// `ty` with references to this module's type definitions replaced by
+    /// what they define, and singletons of one basic type, or unions of
+    /// them, by that type, as `int` for `0|1`. `None` if `ty` refers to a
+    /// type that is not known here.
+    fn resolve_type(&self, ty: &Type, seen: &mut HashSet<String>) -> Option<Type> {
+        match ty {
+            Type::Reference { prefix: None, name } if seen.insert(name.clone()) => {
+                let definition = self
+                    .source_file
+                    .type_definitions()
+                    .find(|definition| definition.name().as_ref() == Some(name))?;
+                self.resolve_type(&definition.declared_type()?, seen)
+            }
+            Type::Reference { .. } => None,
+            Type::Singleton(value) => Type::of_singleton(value),
+            Type::Union(members) => {
+                let members = members
+                    .iter()
+                    .map(|member| self.resolve_type(member, &mut seen.clone()))
+                    .collect::<Option<Vec<_>>>()?;
+                match members.split_first() {
+                    Some((first, rest)) if rest.iter().all(|member| member == first) => {
+                        Some(first.clone())
+                    }
+                    _ => Some(Type::Union(members)),
+                }
+            }
+            other => Some(other.clone()),
+        }
+    }
+
+    /// The fields of an included record parameter's type, with those of the
+    /// types it includes in turn, or `None` if the type is not a record
+    /// defined in this module
+    fn included_fields(&self, param: &Param) -> Option<Vec<RecordField>> {
+        self.record_fields(&param.declared_type()?, &mut HashSet::new())
+    }
+
+    fn record_fields(&self, ty: &Type, seen: &mut HashSet<String>) -> Option<Vec<RecordField>> {
+        match ty {
+            Type::Record {
+                fields, includes, ..
+            } => {
+                let mut all = fields.clone();
+                for included in includes {
+                    all.extend(self.record_fields(included, seen)?);
+                }
+                Some(all)
+            }
+            Type::Reference { prefix: None, name } if seen.insert(name.clone()) => {
+                let definition = self
+                    .source_file
+                    .type_definitions()
+                    .find(|definition| definition.name().as_ref() == Some(name))?;
+                self.record_fields(&definition.declared_type()?, seen)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whether the literal `token` is the operand of a `-` prefix
+fn is_negated(token: &SyntaxToken<BallerinaLanguage>) -> bool {
+    let Some(parent) = token.parent() else {
+        return false;
+    };
+    let operand = match parent.kind() {
+        SyntaxKind::PRIMARY_EXPR => match parent.parent() {
+            Some(unary) if unary.kind() == SyntaxKind::UNARY_EXPR => unary,
+            _ => return false,
+        },
+        SyntaxKind::SINGLETON_TYPE_DESC => parent,
+        _ => return false,
+    };
+    operand
+        .children_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .any(|token| token.kind() == SyntaxKind::MINUS)
+}
+
+/// The next token after `token` that is not whitespace or a comment
+fn next_non_trivia(
+    token: &SyntaxToken<BallerinaLanguage>,
+) -> Option<SyntaxToken<BallerinaLanguage>> {
+    token
+        .siblings_with_tokens(Direction::Next)
+        .skip(1)
+        .filter_map(NodeOrToken::into_token)
+        .find(|token| !token.kind().is_trivia())
+}
+
+/// A node's range without its leading whitespace and comments
+fn node_range(node: &rowan::SyntaxNode<BallerinaLanguage>) -> TextRange {
+    let start = node
+        .descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .find(|token| !token.kind().is_trivia())
+        .map_or(node.text_range().start(), |token| {
+            token.text_range().start()
+        });
+    TextRange::new(start, node.text_range().end())
 }
@@ -0,0 +1,321 @@
+#[cfg(test)]
+mod tests {
+    use crate::semantic::SemanticAnalyzer;
+    use crate::SourceFile;
+    use bal_parser::Parser;
+    use bal_syntax::diagnostic::{codes, Diagnostic};
+    use bal_syntax::source::FileId;
+    use rowan::SyntaxNode;
+
+    fn analyze(input: &str) -> Result<(), Vec<String>> {
+        analyze_diagnostics(input)
+            .map_err(|diagnostics| diagnostics.into_iter().map(|d| d.message).collect())
+    }
+
+    fn analyze_diagnostics(input: &str) -> Result<(), Vec<Diagnostic>> {
+        let tokens = bal_syntax::lexer::lex(FileId::default(), input).tokens;
+        let (green, _) = Parser::new(FileId::default(), input, tokens).parse();
+        SemanticAnalyzer::new(
+            FileId::default(),
+            SourceFile::new(SyntaxNode::new_root(green)),
+        )
+        .analyze()
+    }
+
+    #[test]
+    fn test_distinct_functions() {
+        assert_eq!(analyze("function f() { }\nfunction g() { }"), Ok(()));
+    }
+
+    #[test]
+    fn test_quoted_identifier_is_same_name() {
+        assert_eq!(
+            analyze("function foo() { }\nfunction 'foo() { }"),
+            Err(vec!["duplicate definition of function 'foo'".to_string()])
+        );
+        assert_eq!(
+            analyze("function a\\-b() { }\nfunction 'a\\u{2D}b() { }"),
+            Err(vec!["duplicate definition of function 'a-b'".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_duplicate_points_at_both_definitions() {
+        let diagnostics = analyze_diagnostics("function f() { }\nfunction f() { }").unwrap_err();
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.code, codes::DUPLICATE_DEFINITION);
+        assert_eq!(diagnostic.span().map(|span| span.start()), Some(26));
+        assert_eq!(diagnostic.labels[0].span.start(), 9);
+        assert_eq!(diagnostic.labels[0].message, "first defined here");
+    }
+
+    #[test]
+    fn test_unused_import_is_removed_by_its_fix() {
+        let input =
+            "import ballerina/io;\nimport ballerina/http;\n\nfunction f() { io:println(); }";
+        let diagnostics = analyze_diagnostics(input).unwrap_err();
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.code, codes::UNUSED_IMPORT);
+        assert!(!diagnostic.is_error());
+        assert_eq!(diagnostic.message, "unused import 'http'");
+        assert_eq!(
+            diagnostic.fixes[0].edits[0].apply(input),
+            input.replace("import ballerina/http;\n", "")
+        );
+
+        assert_eq!(
+            analyze("import ballerina/'io;\nfunction f() { io : x; }"),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_import_prefixes() {
+        assert_eq!(
+            analyze(
+                "import ballerina/lang.'int as ints;\nimport myorg/init as _;\n\
+                 function f() { ints:abs(1); }"
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            analyze("import myorg/pkg.sub;\nimport util as u;"),
+            Err(vec![
+                "unused import 'sub'".to_string(),
+                "unused import 'u'".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_declarations_share_one_namespace() {
+        assert_eq!(
+            analyze("const x = 1;\nint x = 2;\ntype x int;\nfunction x() { }"),
+            Err(vec![
+                "duplicate definition of variable 'x'".to_string(),
+                "duplicate definition of type 'x'".to_string(),
+                "duplicate definition of function 'x'".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_cyclic_initializer() {
+        let input = "int total = count();\nfunction count() returns int { return total; }";
+        let diagnostics = analyze_diagnostics(input).unwrap_err();
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.code, codes::CYCLIC_INITIALIZATION);
+        assert_eq!(
+            diagnostic.message,
+            "initializer of 'total' depends on itself"
+        );
+        assert_eq!(diagnostic.span().map(|span| span.start()), Some(4));
+        assert_eq!(diagnostic.labels[0].message, "'count' is part of the cycle");
+        assert_eq!(diagnostic.notes, ["cycle: total -> count -> total"]);
+
+        assert_eq!(
+            analyze("configurable int port = ?;\nfinal int next = port + 1;"),
+            Ok(())
+        );
+    }
+
+    const CONNECT: &str = "function connect(string host, int retries = 3, float... delays) { }\n";
+
+    fn call(args: &str) -> Result<(), Vec<String>> {
+        analyze(&format!(
+            "{}function main() {{ connect({}); }}",
+            CONNECT, args
+        ))
+    }
+
+    #[test]
+    fn test_call_arguments_match_parameters() {
+        assert_eq!(call("\"a\""), Ok(()));
+        assert_eq!(call("\"a\", 5, 1, 2.5"), Ok(()));
+        assert_eq!(call("host = \"a\", retries = 5"), Ok(()));
+        assert_eq!(call("\"a\", retries = 5"), Ok(()));
+        assert_eq!(
+            call(""),
+            Err(vec![
+                "missing argument for parameter 'host' in call to 'connect'".to_string()
+            ])
+        );
+        assert_eq!(
+            call("\"a\", timeout = 1"),
+            Err(vec![
+                "'connect' has no parameter named 'timeout'".to_string()
+            ])
+        );
+        assert_eq!(
+            call("\"a\", host = \"b\""),
+            Err(vec![
+                "argument for 'host' is given more than once".to_string()
+            ])
+        );
+        assert_eq!(
+            analyze("function f(int a) { }\nfunction main() { f(1, 2); }"),
+            Err(vec!["too many arguments in call to 'f'".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_literal_argument_types() {
+        assert_eq!(
+            call("\"a\", \"3\""),
+            Err(vec![
+                "argument for parameter 'retries' has the wrong type".to_string()
+            ])
+        );
+        let diagnostics = analyze_diagnostics(&format!(
+            "{}function main() {{ connect(\"a\", 1, 2.5f, true); }}",
+            CONNECT
+        ))
+        .unwrap_err();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, codes::ARGUMENT_TYPE_MISMATCH);
+        assert_eq!(
+            diagnostics[0]
+                .labels
+                .first()
+                .map(|label| label.message.as_str()),
+            Some("parameter declared here")
+        );
+        assert_eq!(
+            diagnostics[0]
+                .primary
+                .as_ref()
+                .map(|label| label.message.as_str()),
+            Some("expected 'float', found 'boolean'")
+        );
+        assert_eq!(
+            analyze("function f(decimal d, int|string? x) { }\nfunction main() { f(1.5, \"x\"); }"),
+            Ok(())
+        );
+        let diagnostics =
+            analyze_diagnostics("function f(int? x, int y) { }\nfunction main() { f((), ( )); }")
+                .unwrap_err();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0]
+                .primary
+                .as_ref()
+                .map(|label| label.message.as_str()),
+            Some("expected 'int', found '()'")
+        );
+    }
+
+    #[test]
+    fn test_included_record_fields_as_named_arguments() {
+        let input = "type Options record {| int port; boolean tls = false; string? name?; |};\n\
+                     function serve(string host, *Options opts) { }\n";
+        let call =
+            |args: &str| analyze(&format!("{}function main() {{ serve({}); }}", input, args));
+        assert_eq!(call("\"a\", port = 80, tls = true"), Ok(()));
+        assert_eq!(call("\"a\", opts = o"), Ok(()));
+        assert_eq!(
+            call("\"a\", tls = true"),
+            Err(vec![
+                "missing argument for field 'port' of 'opts' in call to 'serve'".to_string()
+            ])
+        );
+        assert_eq!(
+            call("\"a\", port = 80, secure = true"),
+            Err(vec!["'serve' has no parameter named 'secure'".to_string()])
+        );
+        // The fields of a record from another module are not known here
+        assert_eq!(
+            analyze("function f(*http:Options opts) { }\nfunction main() { f(anything = 1); }"),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_operator_operand_types() {
+        let input = "const MASK = 0xFF;\n\
+                     function f(int n, boolean b, float x) returns boolean {\n\
+                     int m = n & MASK >> 2 ^ ~n;\n\
+                     return b && n > 0 || !b ? true : m === 1;\n}";
+        assert_eq!(analyze(input), Ok(()));
+        assert_eq!(
+            analyze("function f(int n, float x) { int m = n ? 1 : x << 2 && true; }"),
+            Err(vec![
+                "condition has type 'int', not 'boolean'".to_string(),
+                "operator '&&' cannot be applied to 'int'".to_string(),
+                "operator '<<' cannot be applied to 'float'".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_operator_type_points_at_operand() {
+        let diagnostics =
+            analyze_diagnostics("function f(string s) { boolean b = true || (s); }").unwrap_err();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, codes::OPERATOR_TYPE_MISMATCH);
+        assert_eq!(diagnostics[0].span().map(|span| span.start()), Some(43));
+        assert_eq!(
+            diagnostics[0]
+                .primary
+                .as_ref()
+                .map(|label| label.message.as_str()),
+            Some("expected 'boolean'")
+        );
+        // The types of calls to other modules are not known
+        assert_eq!(
+            analyze("function f() { boolean b = io:read() && true; }"),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_int_min_must_be_negated() {
+        assert_eq!(
+            analyze(
+                "type Min -9223372036854775808;\n\
+                 function f() { int x = -9223372036854775808; }"
+            ),
+            Ok(())
+        );
+        let diagnostics =
+            analyze_diagnostics("function f() { int x = 1 - 9223372036854775808; }").unwrap_err();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, codes::INVALID_NUMBER);
+        assert_eq!(
+            diagnostics[0]
+                .primary
+                .as_ref()
+                .map(|label| label.message.as_str()),
+            Some("int literal '9223372036854775808' is out of range for int")
+        );
+    }
+
+    #[test]
+    fn test_operator_operands_resolve_type_definitions() {
+        let input = "type Flag boolean;\ntype Bit 0|1;\ntype Mask Bit;\n\
+                     function f(Flag a, boolean b, Bit x, Mask y, io:Num z) returns boolean {\n\
+                     int m = x & y | z;\n\
+                     return a && b;\n}";
+        assert_eq!(analyze(input), Ok(()));
+        assert_eq!(
+            analyze("type Name \"a\"|\"b\";\nfunction f(Name n) { boolean b = n || true; }"),
+            Err(vec!["operator '||' cannot be applied to 'Name'".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_if_and_while_conditions_are_boolean() {
+        assert_eq!(
+            analyze(
+                "function f(int n, boolean b) {\n\
+                 if n { }\nwhile b { }\nif b { } else if n - 1 { }\n}"
+            ),
+            Err(vec![
+                "condition has type 'int', not 'boolean'".to_string(),
+                "condition has type 'int', not 'boolean'".to_string(),
+            ])
+        );
+    }
+}
@@ -1,11 +1,322 @@
 //! Type system for Ballerina AST
 
+use bal_syntax::literal::{self, NumericValue};
+use bal_syntax::{BallerinaLanguage, SyntaxKind};
+use rowan::SyntaxNode;
+use std::fmt;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Type {
     Int,
     Float,
+    Decimal,
     String,
     Boolean,
     NIL,
-    // Add more types as needed
+    Any,
+    Anydata,
+    Json,
+    Never,
+    Readonly,
+    /// `T|U`, flattened, so `int|(string|())` has three members; `T?` is
+    /// `T|()`
+    Union(Vec<Type>),
+    /// `T[]`, or `T[n]` with a literal length. A length given by a constant
+    /// or `*` is not known here.
+    Array {
+        element: Box<Type>,
+        length: Option<u64>,
+    },
+    Tuple {
+        members: Vec<Type>,
+        rest: Option<Box<Type>>,
+    },
+    Map(Box<Type>),
+    /// An inline record. An open record `record { }` takes any other field
+    /// as `anydata`; a closed one only those its rest descriptor allows.
+    Record {
+        fields: Vec<RecordField>,
+        /// Types whose fields are included with `*T;`
+        includes: Vec<Type>,
+        rest: Option<Box<Type>>,
+    },
+    /// A type with one value, kept as the literal is written, such as `-1`
+    Singleton(String),
+    /// A type named by a definition, possibly in another module
+    Reference {
+        prefix: Option<String>,
+        name: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordField {
+    pub name: String,
+    pub ty: Type,
+    /// Declared as `T name?;`
+    pub optional: bool,
+    /// Declared as `T name = default;`
+    pub has_default: bool,
+    pub readonly: bool,
+}
+
+impl RecordField {
+    /// Whether a value of the record must give this field
+    pub fn is_required(&self) -> bool {
+        !self.optional && !self.has_default
+    }
+}
+
+impl Type {
+    /// The type of a literal token, or `None` if the token is not a valid
+    /// literal. The nil literal `()` is given by its `(`.
+    pub fn of_literal(kind: SyntaxKind, text: &str) -> Option<Type> {
+        match kind {
+            SyntaxKind::L_PAREN => Some(Type::NIL),
+            SyntaxKind::INTEGER_LITERAL | SyntaxKind::FLOATING_POINT_LITERAL => {
+                match literal::numeric_value(text).ok()? {
+                    NumericValue::Int(_) => Some(Type::Int),
+                    NumericValue::Float(_) => Some(Type::Float),
+                    NumericValue::Decimal(_) => Some(Type::Decimal),
+                }
+            }
+            SyntaxKind::STRING_LITERAL => Some(Type::String),
+            SyntaxKind::TRUE_KW | SyntaxKind::FALSE_KW => Some(Type::Boolean),
+            _ => None,
+        }
+    }
+
+    /// The basic type of a singleton's value, as `int` for `-1`
+    pub fn of_singleton(value: &str) -> Option<Type> {
+        match value {
+            "true" | "false" => Some(Type::Boolean),
+            _ if value.starts_with('"') => Some(Type::String),
+            _ => Type::of_literal(
+                SyntaxKind::INTEGER_LITERAL,
+                value.strip_prefix('-').unwrap_or(value),
+            ),
+        }
+    }
+
+    /// The type named by a basic type keyword such as `int` or `decimal`
+    pub fn from_keyword(kind: SyntaxKind) -> Option<Type> {
+        match kind {
+            SyntaxKind::INT_KW => Some(Type::Int),
+            SyntaxKind::FLOAT_KW => Some(Type::Float),
+            SyntaxKind::DECIMAL_KW => Some(Type::Decimal),
+            SyntaxKind::BOOLEAN_KW => Some(Type::Boolean),
+            SyntaxKind::STRING_KW => Some(Type::String),
+            SyntaxKind::ANY_KW => Some(Type::Any),
+            SyntaxKind::ANYDATA_KW => Some(Type::Anydata),
+            SyntaxKind::JSON_KW => Some(Type::Json),
+            SyntaxKind::NEVER_KW => Some(Type::Never),
+            SyntaxKind::READONLY_KW => Some(Type::Readonly),
+            _ => None,
+        }
+    }
+
+    /// The type a type descriptor node describes, or `None` if it is
+    /// incomplete
+    pub fn of_type_desc(node: &SyntaxNode<BallerinaLanguage>) -> Option<Type> {
+        match node.kind() {
+            SyntaxKind::TYPE_DESC => Type::from_keyword(node.first_token()?.kind()),
+            SyntaxKind::NIL_TYPE_DESC => Some(Type::NIL),
+            SyntaxKind::PARENTHESIZED_TYPE_DESC => Type::of_type_desc(&type_child(node)?),
+            SyntaxKind::OPTIONAL_TYPE_DESC => Some(Type::union(vec![
+                Type::of_type_desc(&type_child(node)?)?,
+                Type::NIL,
+            ])),
+            SyntaxKind::UNION_TYPE_DESC => node
+                .children()
+                .filter(|child| child.kind().is_type_desc())
+                .map(|child| Type::of_type_desc(&child))
+                .collect::<Option<_>>()
+                .map(Type::union),
+            SyntaxKind::ARRAY_TYPE_DESC => {
+                let length = tokens(node)
+                    .find(|token| token.kind() == SyntaxKind::INTEGER_LITERAL)
+                    .and_then(|token| match literal::numeric_value(token.text()) {
+                        Ok(NumericValue::Int(n)) => u64::try_from(n).ok(),
+                        _ => None,
+                    });
+                Some(Type::Array {
+                    element: Box::new(Type::of_type_desc(&type_child(node)?)?),
+                    length,
+                })
+            }
+            SyntaxKind::TUPLE_TYPE_DESC => {
+                let mut members = Vec::new();
+                let mut rest = None;
+                for child in node.children() {
+                    if child.kind() == SyntaxKind::TUPLE_REST_DESC {
+                        rest = Some(Box::new(Type::of_type_desc(&type_child(&child)?)?));
+                    } else if child.kind().is_type_desc() {
+                        members.push(Type::of_type_desc(&child)?);
+                    }
+                }
+                Some(Type::Tuple { members, rest })
+            }
+            SyntaxKind::MAP_TYPE_DESC => {
+                Some(Type::Map(Box::new(Type::of_type_desc(&type_child(node)?)?)))
+            }
+            SyntaxKind::RECORD_TYPE_DESC => Type::of_record(node),
+            SyntaxKind::SINGLETON_TYPE_DESC => Some(Type::Singleton(
+                tokens(node)
+                    .filter(|token| !token.kind().is_trivia())
+                    .map(|token| token.text().to_string())
+                    .collect(),
+            )),
+            SyntaxKind::TYPE_REFERENCE => {
+                let names: Vec<_> = node
+                    .descendants_with_tokens()
+                    .filter_map(|element| element.into_token())
+                    .filter(|token| token.kind() == SyntaxKind::IDENTIFIER)
+                    .map(|token| crate::identifier_name(&token))
+                    .collect();
+                match names.as_slice() {
+                    [name] => Some(Type::Reference {
+                        prefix: None,
+                        name: name.clone(),
+                    }),
+                    [prefix, name] => Some(Type::Reference {
+                        prefix: Some(prefix.clone()),
+                        name: name.clone(),
+                    }),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn of_record(node: &SyntaxNode<BallerinaLanguage>) -> Option<Type> {
+        let mut fields = Vec::new();
+        let mut includes = Vec::new();
+        // Open records allow any other field
+        let mut rest = tokens(node)
+            .any(|token| token.kind() == SyntaxKind::L_BRACE)
+            .then(|| Box::new(Type::Anydata));
+        for child in node.children() {
+            match child.kind() {
+                SyntaxKind::RECORD_FIELD => fields.push(RecordField {
+                    name: crate::identifier_name(&crate::first_identifier(&child)?),
+                    ty: Type::of_type_desc(&type_child(&child)?)?,
+                    optional: tokens(&child).any(|token| token.kind() == SyntaxKind::QUESTION),
+                    has_default: tokens(&child).any(|token| token.kind() == SyntaxKind::ASSIGN),
+                    readonly: tokens(&child).any(|token| token.kind() == SyntaxKind::READONLY_KW),
+                }),
+                SyntaxKind::TYPE_INCLUSION => {
+                    includes.push(Type::of_type_desc(&type_child(&child)?)?)
+                }
+                SyntaxKind::RECORD_REST_DESC => {
+                    rest = Some(Box::new(Type::of_type_desc(&type_child(&child)?)?))
+                }
+                _ => {}
+            }
+        }
+        Some(Type::Record {
+            fields,
+            includes,
+            rest,
+        })
+    }
+
+    /// Whether a literal token can be a value of this type. Numeric literals
+    /// without a suffix take the numeric type expected of them, so `1` may be
+    /// a `float` and `1.5` a `decimal`. A type named by another definition is
+    /// not resolved here, and admits anything.
+    pub fn admits_literal(&self, kind: SyntaxKind, text: &str) -> bool {
+        let Some(literal) = Type::of_literal(kind, text) else {
+            return true;
+        };
+        match self {
+            Type::Float => matches!(literal, Type::Int | Type::Float),
+            Type::Decimal => {
+                literal == Type::Decimal
+                    || literal == Type::Int
+                    || (literal == Type::Float && !text.ends_with(['f', 'F']))
+            }
+            Type::Any | Type::Anydata | Type::Json | Type::Readonly => true,
+            Type::Union(members) => members
+                .iter()
+                .any(|member| member.admits_literal(kind, text)),
+            Type::Singleton(value) => value == text,
+            Type::Reference { .. } => true,
+            other => *other == literal,
+        }
+    }
+
+    /// A union of `members`, with nested unions flattened into it
+    fn union(members: Vec<Type>) -> Type {
+        Type::Union(
+            members
+                .into_iter()
+                .flat_map(|member| match member {
+                    Type::Union(inner) => inner,
+                    other => vec![other],
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Types are written as in source, so they can be quoted in messages
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Int => f.write_str("int"),
+            Type::Float => f.write_str("float"),
+            Type::Decimal => f.write_str("decimal"),
+            Type::String => f.write_str("string"),
+            Type::Boolean => f.write_str("boolean"),
+            Type::NIL => f.write_str("()"),
+            Type::Any => f.write_str("any"),
+            Type::Anydata => f.write_str("anydata"),
+            Type::Json => f.write_str("json"),
+            Type::Never => f.write_str("never"),
+            Type::Readonly => f.write_str("readonly"),
+            Type::Union(members) => {
+                let members: Vec<_> = members.iter().map(Type::to_string).collect();
+                f.write_str(&members.join("|"))
+            }
+            Type::Array { element, length } => {
+                match **element {
+                    Type::Union(_) => write!(f, "({})", element)?,
+                    _ => write!(f, "{}", element)?,
+                }
+                match length {
+                    Some(length) => write!(f, "[{}]", length),
+                    None => f.write_str("[]"),
+                }
+            }
+            Type::Tuple { members, rest } => {
+                let mut members: Vec<_> = members.iter().map(Type::to_string).collect();
+                if let Some(rest) = rest {
+                    members.push(format!("{}...", rest));
+                }
+                write!(f, "[{}]", members.join(", "))
+            }
+            Type::Map(constraint) => write!(f, "map<{}>", constraint),
+            Type::Record { .. } => f.write_str("record"),
+            Type::Singleton(value) => f.write_str(value),
+            Type::Reference {
+                prefix: Some(prefix),
+                name,
+            } => write!(f, "{}:{}", prefix, name),
+            Type::Reference { prefix: None, name } => f.write_str(name),
+        }
+    }
+}
+
+/// The first type descriptor directly in `node`
+fn type_child(node: &SyntaxNode<BallerinaLanguage>) -> Option<SyntaxNode<BallerinaLanguage>> {
+    node.children().find(|child| child.kind().is_type_desc())
+}
+
+/// The tokens directly in `node`
+fn tokens(
+    node: &SyntaxNode<BallerinaLanguage>,
+) -> impl Iterator<Item = rowan::SyntaxToken<BallerinaLanguage>> {
+    node.children_with_tokens()
+        .filter_map(|element| element.into_token())
 }
@@ -0,0 +1,138 @@
+#[cfg(test)]
+mod tests {
+    use crate::types::{RecordField, Type};
+    use crate::SourceFile;
+    use bal_parser::Parser;
+    use bal_syntax::source::FileId;
+    use rowan::SyntaxNode;
+
+    /// The type `desc` describes, as the type of a definition
+    fn type_of(desc: &str) -> Option<Type> {
+        let input = format!("type T {};", desc);
+        let tokens = bal_syntax::lexer::lex(FileId::default(), &input).tokens;
+        let (green, _) = Parser::new(FileId::default(), &input, tokens).parse();
+        SourceFile::new(SyntaxNode::new_root(green))
+            .type_definitions()
+            .next()?
+            .declared_type()
+    }
+
+    fn reference(name: &str) -> Type {
+        Type::Reference {
+            prefix: None,
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_basic_types() {
+        assert_eq!(type_of("string"), Some(Type::String));
+        assert_eq!(type_of("anydata"), Some(Type::Anydata));
+        assert_eq!(type_of("()"), Some(Type::NIL));
+        assert_eq!(type_of("(json)"), Some(Type::Json));
+    }
+
+    #[test]
+    fn test_optional_is_a_flat_union_with_nil() {
+        assert_eq!(
+            type_of("int|(string|boolean)?"),
+            Some(Type::Union(vec![
+                Type::Int,
+                Type::String,
+                Type::Boolean,
+                Type::NIL
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_postfix_binds_tighter_than_union() {
+        assert_eq!(
+            type_of("int|string?[3]"),
+            Some(Type::Union(vec![
+                Type::Int,
+                Type::Array {
+                    element: Box::new(Type::Union(vec![Type::String, Type::NIL])),
+                    length: Some(3),
+                },
+            ]))
+        );
+        assert_eq!(
+            type_of("int[*]"),
+            Some(Type::Array {
+                element: Box::new(Type::Int),
+                length: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_tuples_maps_and_references() {
+        assert_eq!(
+            type_of("[int, Point, decimal...]"),
+            Some(Type::Tuple {
+                members: vec![Type::Int, reference("Point")],
+                rest: Some(Box::new(Type::Decimal)),
+            })
+        );
+        assert_eq!(
+            type_of("map<io:Name>"),
+            Some(Type::Map(Box::new(Type::Reference {
+                prefix: Some("io".to_string()),
+                name: "Name".to_string(),
+            })))
+        );
+    }
+
+    #[test]
+    fn test_singletons() {
+        assert_eq!(
+            type_of("\"on\"|-1"),
+            Some(Type::Union(vec![
+                Type::Singleton("\"on\"".to_string()),
+                Type::Singleton("-1".to_string()),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_open_and_closed_records() {
+        let field = RecordField {
+            name: "id".to_string(),
+            ty: Type::Int,
+            optional: true,
+            has_default: false,
+            readonly: true,
+        };
+        assert_eq!(
+            type_of("record { readonly int id?; }"),
+            Some(Type::Record {
+                fields: vec![field],
+                includes: vec![],
+                rest: Some(Box::new(Type::Anydata)),
+            })
+        );
+        assert_eq!(
+            type_of("record {| *Base; |}"),
+            Some(Type::Record {
+                fields: vec![],
+                includes: vec![reference("Base")],
+                rest: None,
+            })
+        );
+        assert_eq!(
+            type_of("record {| string...; |}"),
+            Some(Type::Record {
+                fields: vec![],
+                includes: vec![],
+                rest: Some(Box::new(Type::String)),
+            })
+        );
+    }
+
+    #[test]
+    fn test_incomplete_type_has_no_type() {
+        assert_eq!(type_of("map<>"), None);
+        assert_eq!(type_of("record {| *; |}"), None);
+    }
+}
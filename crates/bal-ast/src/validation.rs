@@ -1,10 +1,7 @@
 //! AST validation rules and error reporting
 
-pub struct ValidationError {
-    pub message: String,
-    pub span: Option<rowan::TextRange>,
-}
+use bal_syntax::diagnostic::Diagnostic;
 
-pub fn validate_ast(ast: &crate::SourceFile) -> Vec<ValidationError> {
+pub fn validate_ast(_ast: &crate::SourceFile) -> Vec<Diagnostic> {
     Vec::new() // TODO: Implement validation rules
 }
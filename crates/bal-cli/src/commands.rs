@@ -2,17 +2,26 @@
 
 use crate::config::Config;
 use crate::dependency::{build_project_dependencies, DependencyGraph, ModuleId};
+use crate::diff::unified_diff;
+use crate::output::{self, MessageFormat};
+use bal_ast::semantic::SemanticAnalyzer;
+use bal_ast::SourceFile;
 use bal_parser::Parser;
-use bal_syntax::lexer::Lexer;
+use bal_syntax::diagnostic::{Diagnostic, Diagnostics};
 use bal_syntax::project::Project;
+use bal_syntax::render::{summary, Renderer};
+use bal_syntax::source::{FileId, SourceDb};
+use bal_syntax::text_edit::apply_edits;
+use rowan::{GreenNode, NodeCache, SyntaxNode};
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 fn is_bal_file(path: &Path) -> bool {
     path.extension()
         .and_then(|ext| ext.to_str())
-        .map_or(false, |ext| ext == "bal")
+        .is_some_and(|ext| ext == "bal")
 }
 
 pub fn build(input: Option<PathBuf>, config: &Config) -> Result<(), String> {
@@ -58,17 +67,32 @@ fn build_single_file(path: &Path, config: &Config) -> Result<(), String> {
     }
 
     let mut dep_graph = DependencyGraph::new();
-    parse_and_build_file(path, config, &mut dep_graph)
+    let mut session = Session::default();
+    let result = parse_and_build_file(path, config, &mut dep_graph, &mut session);
+    session.report(config);
+    result
 }
 
 fn build_project_from_path(project_path: &Path, config: &Config) -> Result<(), String> {
-    let project =
-        Project::load(project_path).map_err(|e| format!("Failed to load project: {}", e))?;
+    let project = match Project::load(project_path) {
+        Ok(project) => project,
+        Err(e) => {
+            let diagnostic = Diagnostic::from(&e);
+            let message = format!("Failed to load project: {}", diagnostic);
+            // Human output already shows the message as the command's error
+            if config.message_format != MessageFormat::Human {
+                let mut session = Session::default();
+                session.diagnostics.push(diagnostic);
+                session.report(config);
+            }
+            return Err(message);
+        }
+    };
 
-    println!(
+    config.status(&format!(
         "Building Ballerina project: {}/{} v{}",
         project.package.info.org, project.package.info.name, project.package.info.version
-    );
+    ));
 
     config.debug(&format!(
         "Project directory: {}",
@@ -87,44 +111,44 @@ fn build_project_from_path(project_path: &Path, config: &Config) -> Result<(), S
     let build_tasks: Vec<_> = dep_graph
         .build_order()
         .into_iter()
-        .filter_map(|id| {
-            dep_graph
-                .module_files
-                .get(&id)
-                .map(|path| (id, path.clone()))
+        .flat_map(|id| {
+            let files = dep_graph.module_files.get(&id).cloned().unwrap_or_default();
+            files.into_iter().map(move |path| (id.clone(), path))
         })
         .collect();
 
     // Now do the mutable operations
     let mut project_had_errors = false;
+    let mut session = Session::default();
     for (_module_id, file_path) in build_tasks {
         config.debug(&format!("\nBuilding file: {}", file_path.display()));
-        if let Err(e) = parse_and_build_file(&file_path, config, &mut dep_graph) {
+        if let Err(e) = parse_and_build_file(&file_path, config, &mut dep_graph, &mut session) {
             eprintln!("Error in {}: {}", file_path.display(), e);
             project_had_errors = true;
         }
     }
+    session.report(config);
 
     // Print dependency tree only in debug mode
     config.debug("\nDependency Tree:");
     if config.debug {
-        print_dependency_tree(&dep_graph.dependencies);
+        print_dependency_tree(&dep_graph.dependencies, config);
     }
 
-    if project_had_errors {
+    if project_had_errors || session.diagnostics.has_errors() {
         Err("Project build completed with errors".to_string())
     } else {
-        println!("Build successful");
+        config.status("Build successful");
         Ok(())
     }
 }
 
 /// Print dependency tree with nice formatting
-fn print_dependency_tree(deps: &HashMap<ModuleId, HashSet<ModuleId>>) {
+fn print_dependency_tree(deps: &HashMap<ModuleId, HashSet<ModuleId>>, config: &Config) {
     for (module, dependencies) in deps {
-        println!("{}:", module);
+        config.status(&format!("{}:", module));
         for dep in dependencies {
-            println!("  └─ {}", dep);
+            config.status(&format!("  └─ {}", dep));
         }
     }
 }
@@ -133,60 +157,235 @@ fn parse_and_build_file(
     path: &Path,
     config: &Config,
     dep_graph: &mut DependencyGraph,
+    session: &mut Session,
 ) -> Result<(), String> {
     let source = fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
 
     // Check if we have a valid cached parse tree
     if let Some(parse_tree) = dep_graph.get_cached_parse(path, &source) {
         config.debug(&format!("Using cached parse tree for {}", path.display()));
-        println!("Parse tree:\n{:#?}", parse_tree);
+        config.status(&format!("Parse tree:\n{:#?}", parse_tree));
         return Ok(());
     }
 
     // If no cache hit, parse the file
     config.debug(&format!("Parsing file {}", path.display()));
 
-    // Tokenize with error handling
-    let mut tokens = Vec::new();
-    let mut lexer = Lexer::new(&source);
-    let mut had_errors = false;
-
-    while let Some(result) = lexer.next_token() {
-        match result {
-            Ok(token_info) => {
-                if !matches!(token_info.kind, bal_syntax::lexer::Token::Newline) {
-                    let kind = bal_syntax::convert_token(token_info.kind);
-                    tokens.push((kind, token_info.text, token_info.span));
+    // Keep the text in memory so errors can show source lines
+    let file = session
+        .db
+        .add_file(path.display().to_string(), source.as_str());
+    let errors_before = session.diagnostics.error_count();
+
+    let (parse_tree, diagnostics) = check_file(file, &source, &mut session.cache);
+    session.diagnostics.extend(diagnostics);
+    config.debug(&format!("Parse tree:\n{:#?}", parse_tree));
+    dep_graph.cache_parse(path.to_path_buf(), &source, parse_tree);
+
+    if session.diagnostics.error_count() > errors_before {
+        Err("Completed with errors".to_string())
+    } else {
+        config.debug(&format!("Successfully parsed: {}", path.display()));
+        Ok(())
+    }
+}
+
+/// Lex, parse and analyze one file. The tree covers the whole file even
+/// when it has syntax errors.
+fn check_file(file: FileId, source: &str, cache: &mut NodeCache) -> (GreenNode, Diagnostics) {
+    let mut diagnostics = Diagnostics::new();
+
+    // Tokenize with error handling; invalid input is kept as ERROR tokens
+    let lexed = bal_syntax::lexer::lex(file, source);
+    diagnostics.extend(lexed.diagnostics);
+
+    let (parse_tree, parse_diagnostics) =
+        Parser::with_cache(file, source, lexed.tokens, cache).parse();
+    diagnostics.extend(parse_diagnostics);
+    let source_file = SourceFile::new(SyntaxNode::new_root(parse_tree.clone()));
+    if let Err(semantic) = SemanticAnalyzer::new(file, source_file).analyze() {
+        diagnostics.extend(semantic);
+    }
+    (parse_tree, diagnostics)
+}
+
+/// The `.bal` files of the project at `path`, or `path` itself if it is a file
+fn source_files(path: &Path) -> Result<Vec<PathBuf>, String> {
+    if path.is_dir() {
+        Ok(Project::load(path)
+            .map_err(|e| format!("Failed to load project: {}", e))?
+            .source_files)
+    } else if is_bal_file(path) {
+        Ok(vec![path.to_path_buf()])
+    } else {
+        Err(format!("Not a Ballerina file: {}", path.display()))
+    }
+}
+
+/// What one build shares across the files it compiles
+#[derive(Default)]
+struct Session {
+    /// Every file read so far, so diagnostics can show source lines
+    db: SourceDb,
+    /// Shared so identical tokens and subtrees are allocated once per build
+    cache: NodeCache,
+    diagnostics: Diagnostics,
+}
+
+impl Session {
+    /// Print every diagnostic in the configured format. Human output ends
+    /// with how many errors and warnings there were.
+    fn report(&self, config: &Config) {
+        match config.message_format {
+            MessageFormat::Human => {
+                if self.diagnostics.is_empty() {
+                    return;
+                }
+                let renderer = Renderer::new(config.color);
+                for diagnostic in &self.diagnostics {
+                    eprintln!("{}", renderer.render(&self.db, diagnostic));
                 }
+                eprintln!("{}", summary(&self.diagnostics));
+                eprintln!("For more information about a code, try `bal explain <code>`");
             }
-            Err(e) => {
-                eprintln!("Lexer error: {}", e);
-                had_errors = true;
+            MessageFormat::Json => print!("{}", output::json_lines(&self.db, &self.diagnostics)),
+            MessageFormat::Sarif => {
+                let log = output::sarif(&self.db, &self.diagnostics);
+                println!("{:#}", log);
             }
         }
     }
+}
 
-    let file_name = path.to_str().map(String::from);
-    let parser = Parser::new(file_name, tokens);
-    match parser.parse() {
-        Ok(parse_tree) => {
-            config.debug(&format!("Parse tree:\n{:#?}", parse_tree));
+/// Time lexing and parsing of a file or project, without building it
+pub fn bench(input: Option<PathBuf>, iterations: u32, config: &Config) -> Result<(), String> {
+    let path = match input {
+        Some(path) => path,
+        None => std::env::current_dir()
+            .map_err(|e| format!("Failed to get current directory: {}", e))?,
+    };
 
-            // Cache successful parse
-            dep_graph.cache_parse(path.to_path_buf(), &source, parse_tree);
+    let files = source_files(&path)?;
 
-            if had_errors {
-                Err("Completed with errors".to_string())
-            } else {
-                config.debug(&format!("Successfully parsed: {}", path.display()));
-                Ok(())
+    let mut db = SourceDb::new();
+    for file in &files {
+        let source = fs::read_to_string(file).map_err(|e| format!("Failed to read file: {}", e))?;
+        db.add_file(file.display().to_string(), source);
+    }
+    let bytes: usize = db.files().map(|(_, file)| file.text.len()).sum();
+    let iterations = iterations.max(1);
+
+    let mut tokens = 0;
+    let mut lex_time = Duration::ZERO;
+    let mut parse_time = Duration::ZERO;
+    for _ in 0..iterations {
+        let mut cache = NodeCache::default();
+        for (id, file) in db.files() {
+            let start = Instant::now();
+            let lexed = bal_syntax::lexer::lex(id, &file.text);
+            lex_time += start.elapsed();
+            tokens += lexed.tokens.len();
+
+            let start = Instant::now();
+            let (_, diagnostics) =
+                Parser::with_cache(id, &file.text, lexed.tokens, &mut cache).parse();
+            parse_time += start.elapsed();
+            for diagnostic in diagnostics {
+                config.debug(&format!("{}: {}", file.path, diagnostic));
             }
         }
-        Err(e) => {
-            eprintln!("Parser error: {}", e);
-            Err("Parser errors encountered".to_string())
+    }
+
+    let total_bytes = (bytes * iterations as usize) as f64;
+    println!(
+        "{} files, {} bytes, {} iterations",
+        files.len(),
+        bytes,
+        iterations
+    );
+    println!(
+        "lex:   {:>10.0} tokens/sec  {:>8.2} MB/s",
+        tokens as f64 / lex_time.as_secs_f64(),
+        total_bytes / 1e6 / lex_time.as_secs_f64()
+    );
+    println!(
+        "parse: {:>10.0} tokens/sec  {:>8.2} MB/s",
+        tokens as f64 / parse_time.as_secs_f64(),
+        total_bytes / 1e6 / parse_time.as_secs_f64()
+    );
+    Ok(())
+}
+
+/// Fixing one problem can reveal another, so fixes are applied in passes
+const MAX_FIX_PASSES: usize = 10;
+
+/// Apply every machine-applicable fix in a file or project. A dry run
+/// prints the changes as a unified diff instead of writing them.
+pub fn fix(input: Option<PathBuf>, dry_run: bool, config: &Config) -> Result<(), String> {
+    let path = match input {
+        Some(path) => path,
+        None => std::env::current_dir()
+            .map_err(|e| format!("Failed to get current directory: {}", e))?,
+    };
+
+    let mut fixed_files = 0;
+    let mut fix_count = 0;
+    for path in source_files(&path)? {
+        let original =
+            fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {}", e))?;
+        let mut text = original.clone();
+        let mut applied = 0;
+        for _ in 0..MAX_FIX_PASSES {
+            let (_, diagnostics) = check_file(FileId::default(), &text, &mut NodeCache::default());
+            let edits = diagnostics
+                .iter()
+                .flat_map(|diagnostic| &diagnostic.fixes)
+                .filter(|fix| fix.is_machine_applicable())
+                .flat_map(|fix| &fix.edits);
+            let (fixed, count) = apply_edits(&text, edits);
+            if fixed == text {
+                break;
+            }
+            text = fixed;
+            applied += count;
+        }
+        if text == original {
+            continue;
+        }
+
+        config.debug(&format!("{} fixes in {}", applied, path.display()));
+        fixed_files += 1;
+        fix_count += applied;
+        if dry_run {
+            print!(
+                "{}",
+                unified_diff(&path.display().to_string(), &original, &text)
+            );
+        } else {
+            fs::write(&path, &text).map_err(|e| format!("Failed to write file: {}", e))?;
         }
     }
+
+    if fixed_files == 0 {
+        config.status("No fixes to apply");
+    } else if !dry_run {
+        config.status(&format!(
+            "Applied {} {} in {} {}",
+            fix_count,
+            if fix_count == 1 { "fix" } else { "fixes" },
+            fixed_files,
+            if fixed_files == 1 { "file" } else { "files" }
+        ));
+    }
+    Ok(())
+}
+
+/// Print the long-form explanation of a diagnostic code
+pub fn explain(code: &str) -> Result<(), String> {
+    let text = bal_syntax::explain::explain(code)
+        .ok_or_else(|| format!("Unknown diagnostic code: {}", code))?;
+    print!("{}", text);
+    Ok(())
 }
 
 pub fn clean(path: Option<PathBuf>, config: &Config) -> Result<(), String> {
@@ -1,4 +1,5 @@
 use crate::config;
+use crate::output::MessageFormat;
 
 use super::*;
 use std::path::PathBuf;
@@ -88,6 +89,89 @@ fn test_build_current_directory() {
     );
 }
 
+#[test]
+fn test_bench_project() {
+    let path = test_dir().join("projects").join("proj1");
+    let result = bench(Some(path), 2, &config::Config::new(false));
+    assert!(result.is_ok(), "Failed to benchmark project: {:?}", result);
+}
+
+#[test]
+fn test_bench_invalid_extension() {
+    let path = test_dir().join("invalid.txt");
+    let result = bench(Some(path), 1, &config::Config::new(false));
+    assert!(result.unwrap_err().contains("Not a Ballerina file"));
+}
+
+#[test]
+fn test_build_exit_status_reflects_errors() {
+    let dir = std::env::temp_dir().join(format!("bal-cli-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("broken.bal");
+    std::fs::write(&path, "function f() { }\nfunction f() { }\n").unwrap();
+
+    for format in [
+        MessageFormat::Human,
+        MessageFormat::Json,
+        MessageFormat::Sarif,
+    ] {
+        let config = config::Config::new(false).with_message_format(format);
+        assert!(build(Some(path.clone()), &config).is_err());
+    }
+    let config = config::Config::new(false).with_message_format(MessageFormat::Json);
+    assert!(build(Some(test_dir().join("exp1.bal")), &config).is_ok());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_fix_applies_machine_applicable_fixes() {
+    let dir = std::env::temp_dir().join(format!("bal-cli-fix-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("main.bal");
+    let source = "import ballerina/io\nimport ballerina/http;\n\nfunction f() { io:println(); }\n";
+    std::fs::write(&path, source).unwrap();
+
+    let config = config::Config::new(false);
+    assert!(fix(Some(path.clone()), true, &config).is_ok());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), source);
+
+    assert!(fix(Some(path.clone()), false, &config).is_ok());
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "import ballerina/io;\n\nfunction f() { io:println(); }\n"
+    );
+    assert!(build(Some(path.clone()), &config).is_ok());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_fix_statement_semicolon_and_condition() {
+    let dir = std::env::temp_dir().join(format!("bal-cli-fix-stmt-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("main.bal");
+    let source = "function f() {\n    int x = 1\n    if x = 2 {\n    }\n}\n";
+    std::fs::write(&path, source).unwrap();
+
+    let config = config::Config::new(false);
+    assert!(fix(Some(path.clone()), false, &config).is_ok());
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "function f() {\n    int x = 1;\n    if x == 2 {\n    }\n}\n"
+    );
+    assert!(build(Some(path.clone()), &config).is_ok());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_explain() {
+    assert!(explain("BCE2001").is_ok());
+    assert!(explain("bce3002").is_ok());
+    assert!(explain("nonsense").is_err());
+}
+
 // Helper function to create test files for setup/teardown if needed
 #[allow(dead_code)]
 fn setup_test_files() -> std::io::Result<()> {
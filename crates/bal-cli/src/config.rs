@@ -1,13 +1,38 @@
 //! CLI configuration settings
 
+use crate::output::MessageFormat;
+use std::io::IsTerminal;
+
 #[derive(Debug, Default)]
 pub struct Config {
     pub debug: bool,
+    /// Whether diagnostics are printed in color
+    pub color: bool,
+    pub message_format: MessageFormat,
 }
 
 impl Config {
     pub fn new(debug: bool) -> Self {
-        Self { debug }
+        Self {
+            debug,
+            color: color_enabled(),
+            message_format: MessageFormat::Human,
+        }
+    }
+
+    pub fn with_message_format(mut self, message_format: MessageFormat) -> Self {
+        self.message_format = message_format;
+        self
+    }
+
+    /// Progress messages go to stdout, unless stdout is reserved for
+    /// machine-readable diagnostics
+    pub fn status(&self, msg: &str) {
+        if self.message_format == MessageFormat::Human {
+            println!("{}", msg);
+        } else {
+            eprintln!("{}", msg);
+        }
     }
 
     pub fn debug(&self, msg: &str) {
@@ -16,3 +41,9 @@ impl Config {
         }
     }
 }
+
+/// Color is used on a terminal unless `NO_COLOR` is set to a non-empty value
+fn color_enabled() -> bool {
+    std::io::stderr().is_terminal()
+        && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
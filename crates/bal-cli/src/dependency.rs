@@ -1,6 +1,10 @@
 use crate::config::Config;
-use bal_syntax::lexer::Lexer;
-use bal_syntax::project::Project;
+use bal_ast::{Import, SourceFile};
+use bal_parser::Parser;
+use bal_syntax::lexer::lex;
+use bal_syntax::project::{PackageInfo, Project};
+use bal_syntax::source::FileId;
+use bal_syntax::BallerinaLanguage;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
@@ -12,8 +16,8 @@ pub struct DependencyGraph {
     pub dependencies: HashMap<ModuleId, HashSet<ModuleId>>,
     /// Map from file path to last modified time
     timestamps: HashMap<PathBuf, SystemTime>,
-    /// Map from module ID to file path
-    pub module_files: HashMap<ModuleId, PathBuf>,
+    /// Map from module ID to the files of the module
+    pub module_files: HashMap<ModuleId, Vec<PathBuf>>,
     /// Map from file path to cached parse results and source hash
     #[serde(skip)] // Don't serialize parse results
     parse_cache: HashMap<PathBuf, (rowan::GreenNode, u64)>,
@@ -22,8 +26,12 @@ pub struct DependencyGraph {
 #[derive(Debug, Hash, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ModuleId {
     org: String,
+    /// The package the module belongs to
     name: String,
-    version: String,
+    /// Only known for modules of the package being built, until
+    /// dependencies are resolved
+    version: Option<String>,
+    /// The full dotted module name, as `lang.int`
     module: String,
 }
 
@@ -78,15 +86,22 @@ impl DependencyGraph {
         true
     }
 
-    /// Add a module and its dependencies to the graph
+    /// Add a file of a module and the file's dependencies to the graph. A
+    /// module depends on everything any of its files imports.
     pub fn add_module(
         &mut self,
         module: ModuleId,
         dependencies: HashSet<ModuleId>,
         file_path: PathBuf,
     ) {
-        self.dependencies.insert(module.clone(), dependencies);
-        self.module_files.insert(module, file_path.clone());
+        self.dependencies
+            .entry(module.clone())
+            .or_default()
+            .extend(dependencies);
+        self.module_files
+            .entry(module)
+            .or_default()
+            .push(file_path.clone());
 
         // Update timestamp
         if let Ok(metadata) = std::fs::metadata(&file_path) {
@@ -177,12 +192,10 @@ pub fn build_project_dependencies(
     // Parse all files to build dependency graph
     for file_path in &project.source_files {
         let source = std::fs::read_to_string(file_path)?;
-        let imports = parse_imports(&source)?;
-
         let module_id = module_id_from_path(project, file_path);
-        let dependencies = imports
-            .into_iter()
-            .map(|imp| ModuleId::from_import(&imp))
+        let dependencies = parse_imports(&source)
+            .iter()
+            .filter_map(|import| ModuleId::from_import(import, &project.package.info))
             .collect();
 
         graph.add_module(module_id, dependencies, file_path.to_path_buf());
@@ -193,81 +206,68 @@ pub fn build_project_dependencies(
     Ok((graph, false))
 }
 
-/// Parse import statements from source code
-fn parse_imports(source: &str) -> std::io::Result<Vec<String>> {
-    let mut lexer = Lexer::new(source);
-    let mut tokens = Vec::new();
-
-    // First tokenize the source
-    while let Some(Ok(token_info)) = lexer.next_token() {
-        if !matches!(token_info.kind, bal_syntax::lexer::Token::Newline) {
-            tokens.push((
-                bal_syntax::convert_token(token_info.kind),
-                token_info.text,
-                token_info.span,
-            ));
-        }
-    }
-
-    let mut imports = Vec::new();
-    let mut i = 0;
-
-    // Parse imports using proper token analysis
-    while i < tokens.len() {
-        if matches!(tokens[i].0, bal_syntax::SyntaxKind::IMPORT_KW) {
-            let mut import_path = Vec::new();
-            i += 1; // Skip 'import' keyword
-
-            // Collect all identifiers and slashes until semicolon
-            while i < tokens.len() {
-                match tokens[i].0 {
-                    bal_syntax::SyntaxKind::IDENTIFIER => {
-                        import_path.push(tokens[i].1.clone());
-                        i += 1;
-                    }
-                    bal_syntax::SyntaxKind::SLASH => {
-                        import_path.push("/".to_string());
-                        i += 1;
-                    }
-                    bal_syntax::SyntaxKind::SEMICOLON => {
-                        i += 1;
-                        break;
-                    }
-                    _ => i += 1,
-                }
-            }
-
-            if !import_path.is_empty() {
-                imports.push(import_path.join(""));
-            }
-        } else {
-            i += 1;
-        }
-    }
-
-    Ok(imports)
+/// The import declarations of a source file, parsed even if the rest of
+/// the file has errors
+fn parse_imports(source: &str) -> Vec<Import> {
+    let tokens = lex(FileId::default(), source).tokens;
+    let (green, _) = Parser::new(FileId::default(), source, tokens).parse();
+    SourceFile::new(rowan::SyntaxNode::<BallerinaLanguage>::new_root(green))
+        .imports()
+        .collect()
 }
 
+/// The module a file of the project belongs to, named the way the
+/// package's other modules import it: the default module `proj1` for a file
+/// in the package root, or `proj1.util` for one in `modules/util`
 fn module_id_from_path(project: &Project, path: &Path) -> ModuleId {
-    // TODO: Extract module info from path relative to project
+    let info = &project.package.info;
+    let relative = path.strip_prefix(&project.root_dir).unwrap_or(path);
+    let components: Vec<_> = relative.components().map(|c| c.as_os_str()).collect();
+    let module = match components.as_slice() {
+        [modules, module, _, ..] if *modules == "modules" => {
+            format!("{}.{}", info.name, module.to_string_lossy())
+        }
+        _ => info.name.clone(),
+    };
     ModuleId {
-        org: project.package.info.org.clone(),
-        name: project.package.info.name.clone(),
-        version: project.package.info.version.clone(),
-        module: path.file_stem().unwrap().to_string_lossy().to_string(),
+        org: info.org.clone(),
+        name: info.name.clone(),
+        version: Some(info.version.clone()),
+        module,
     }
 }
 
 impl ModuleId {
-    fn from_import(import_path: &str) -> Self {
-        // Simple implementation - split on '/'
-        let parts: Vec<&str> = import_path.split('/').collect();
-        Self {
-            org: parts.get(0).unwrap_or(&"").to_string(),
-            name: parts.get(1).unwrap_or(&"").to_string(),
-            version: "0.1.0".to_string(), // Default version
-            module: parts.get(2).unwrap_or(&"").to_string(),
+    /// The module an import refers to, or `None` if the import has no
+    /// module name. Modules of the package being built are named after it,
+    /// as `proj1.util` in package `proj1`; any other module is taken to be
+    /// the default module of a package of the same name.
+    fn from_import(import: &Import, package: &PackageInfo) -> Option<Self> {
+        let module = import.module_name();
+        if module.is_empty() {
+            return None;
         }
+        let module = module.join(".");
+        let org = import.org().unwrap_or_else(|| package.org.clone());
+        let in_package = org == package.org
+            && module
+                .strip_prefix(package.name.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'));
+        Some(if in_package {
+            Self {
+                org,
+                name: package.name.clone(),
+                version: Some(package.version.clone()),
+                module,
+            }
+        } else {
+            Self {
+                org,
+                name: module.clone(),
+                version: None,
+                module,
+            }
+        })
     }
 }
 
@@ -278,3 +278,6 @@ fn calculate_hash(source: &str) -> u64 {
     source.hash(&mut hasher);
     hasher.finish()
 }
+
+#[cfg(test)]
+mod tests;
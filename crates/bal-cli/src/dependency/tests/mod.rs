@@ -0,0 +1,87 @@
+use super::*;
+use bal_syntax::project::Package;
+
+fn package() -> PackageInfo {
+    PackageInfo {
+        org: "myorg".to_string(),
+        name: "proj1".to_string(),
+        version: "1.2.0".to_string(),
+        distribution: String::new(),
+    }
+}
+
+/// The modules `source` imports, as `org/package/module@version`
+fn imported_modules(source: &str) -> Vec<String> {
+    parse_imports(source)
+        .iter()
+        .filter_map(|import| ModuleId::from_import(import, &package()))
+        .map(|id| format!("{}@{}", id, id.version.as_deref().unwrap_or("?")))
+        .collect()
+}
+
+#[test]
+fn test_imports_of_other_packages() {
+    assert_eq!(
+        imported_modules("import ballerina/io;\nimport ballerina/lang.'int as ints;"),
+        ["ballerina/io/io@?", "ballerina/lang.int/lang.int@?"]
+    );
+}
+
+#[test]
+fn test_imports_of_this_package() {
+    assert_eq!(
+        imported_modules("import proj1.util;\nimport myorg/proj1 as _;\nimport proj10;"),
+        [
+            "myorg/proj1/proj1.util@1.2.0",
+            "myorg/proj1/proj1@1.2.0",
+            "myorg/proj10/proj10@?"
+        ]
+    );
+}
+
+#[test]
+fn test_imports_after_syntax_errors_are_found() {
+    assert_eq!(
+        imported_modules("import ballerina/;\nfunction f( {\nimport ballerina/http;"),
+        ["ballerina/http/http@?"]
+    );
+}
+
+#[test]
+fn test_in_package_imports_match_module_files() {
+    let root = std::env::temp_dir().join(format!("bal-cli-deps-test-{}", std::process::id()));
+    let util_dir = root.join("modules").join("util");
+    std::fs::create_dir_all(&util_dir).unwrap();
+    let main = root.join("main.bal");
+    let types = root.join("types.bal");
+    let util = util_dir.join("util.bal");
+    std::fs::write(&main, "import proj1.util;\nfunction main() { }\n").unwrap();
+    std::fs::write(&types, "type Id int;\n").unwrap();
+    std::fs::write(&util, "import ballerina/io;\n").unwrap();
+    let project = Project {
+        root_dir: root.clone(),
+        package: Package {
+            info: package(),
+            build_options: Default::default(),
+        },
+        source_files: vec![main.clone(), types.clone(), util.clone()],
+    };
+
+    let (graph, _) = build_project_dependencies(&project, &Config::new(false)).unwrap();
+    let default_module = module_id_from_path(&project, &main);
+    let util_module = module_id_from_path(&project, &util);
+    assert_eq!(default_module.module, "proj1");
+    assert_eq!(util_module.module, "proj1.util");
+    assert!(graph.dependencies[&default_module].contains(&util_module));
+    assert_eq!(graph.module_files[&default_module], [main, types]);
+    assert_eq!(graph.module_files[&util_module], [util]);
+    let order: Vec<_> = graph
+        .build_order()
+        .into_iter()
+        .map(|id| id.module)
+        .collect();
+    let position = |module: &str| order.iter().position(|m| m == module).unwrap();
+    assert!(position("proj1.util") < position("proj1"));
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,133 @@
+//! Unified diffs of source files, as printed by `bal fix --dry-run`
+
+use std::fmt::Write;
+
+/// Unchanged lines shown around each change
+const CONTEXT: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Line<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// A unified diff from `old` to `new`, or an empty string if they are equal
+pub fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    let old_lines: Vec<_> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<_> = new.split_inclusive('\n').collect();
+    let lines = diff_lines(&old_lines, &new_lines);
+    if lines.iter().all(|line| matches!(line, Line::Same(_))) {
+        return String::new();
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", path, path);
+    for hunk in hunks(&lines) {
+        let (mut old_start, mut new_start) = (1, 1);
+        for line in &lines[..hunk.start] {
+            match line {
+                Line::Same(_) => {
+                    old_start += 1;
+                    new_start += 1;
+                }
+                Line::Removed(_) => old_start += 1,
+                Line::Added(_) => new_start += 1,
+            }
+        }
+        let body = &lines[hunk];
+        let old_len = body
+            .iter()
+            .filter(|line| !matches!(line, Line::Added(_)))
+            .count();
+        let new_len = body
+            .iter()
+            .filter(|line| !matches!(line, Line::Removed(_)))
+            .count();
+        // An empty range is numbered by the line before it
+        let old_start = if old_len == 0 {
+            old_start - 1
+        } else {
+            old_start
+        };
+        let new_start = if new_len == 0 {
+            new_start - 1
+        } else {
+            new_start
+        };
+        let _ = writeln!(
+            out,
+            "@@ -{},{} +{},{} @@",
+            old_start, old_len, new_start, new_len
+        );
+
+        for line in body {
+            let (marker, text) = match line {
+                Line::Same(text) => (' ', text),
+                Line::Removed(text) => ('-', text),
+                Line::Added(text) => ('+', text),
+            };
+            out.push(marker);
+            out.push_str(text);
+            if !text.ends_with('\n') {
+                out.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+    }
+    out
+}
+
+/// The shortest edit script between two lists of lines, from their longest
+/// common subsequence
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Line<'a>> {
+    // lcs[i][j] is the length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::with_capacity(old.len().max(new.len()));
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(Line::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(Line::Removed(old[i]));
+            i += 1;
+        } else {
+            lines.push(Line::Added(new[j]));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().map(|line| Line::Removed(line)));
+    lines.extend(new[j..].iter().map(|line| Line::Added(line)));
+    lines
+}
+
+/// Ranges of `lines` to print: each change with its context, merging
+/// changes whose context would touch
+fn hunks(lines: &[Line]) -> Vec<std::ops::Range<usize>> {
+    let mut hunks: Vec<std::ops::Range<usize>> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if matches!(line, Line::Same(_)) {
+            continue;
+        }
+        let start = i.saturating_sub(CONTEXT);
+        let end = (i + 1 + CONTEXT).min(lines.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.end => last.end = end,
+            _ => hunks.push(start..end),
+        }
+    }
+    hunks
+}
+
+#[cfg(test)]
+mod tests;
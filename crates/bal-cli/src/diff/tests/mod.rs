@@ -0,0 +1,39 @@
+use super::*;
+
+#[test]
+fn test_equal_texts_have_no_diff() {
+    assert_eq!(unified_diff("main.bal", "a\nb\n", "a\nb\n"), "");
+}
+
+#[test]
+fn test_change_with_context() {
+    let old = "1\n2\n3\n4\n5\n6\n7\n8\n";
+    let new = "1\n2\n3\n4\nfive\n6\n7\n8\n";
+    assert_eq!(
+        unified_diff("main.bal", old, new),
+        "--- main.bal\n+++ main.bal\n@@ -2,7 +2,7 @@\n 2\n 3\n 4\n-5\n+five\n 6\n 7\n 8\n"
+    );
+}
+
+#[test]
+fn test_distant_changes_make_separate_hunks() {
+    let old: String = (1..=20).map(|i| format!("{}\n", i)).collect();
+    let new: String = (1..=20)
+        .filter(|&i| i != 2)
+        .map(|i| match i {
+            19 => "19;\n".to_string(),
+            i => format!("{}\n", i),
+        })
+        .collect();
+    let diff = unified_diff("main.bal", &old, &new);
+    let headers: Vec<_> = diff.lines().filter(|line| line.starts_with("@@")).collect();
+    assert_eq!(headers, ["@@ -1,5 +1,4 @@", "@@ -16,5 +15,5 @@"]);
+}
+
+#[test]
+fn test_deleted_first_line() {
+    assert_eq!(
+        unified_diff("a.bal", "import x/y;\nfunction f() { }", "function f() { }"),
+        "--- a.bal\n+++ a.bal\n@@ -1,2 +1,1 @@\n-import x/y;\n function f() { }\n\\ No newline at end of file\n"
+    );
+}
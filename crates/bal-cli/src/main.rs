@@ -1,4 +1,5 @@
 use crate::config::Config;
+use crate::output::MessageFormat;
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
@@ -23,6 +24,10 @@ enum Commands {
         /// Optional path to a .bal file or project directory.
         /// If not provided, attempts to build project in current directory
         input: Option<PathBuf>,
+
+        /// How to print diagnostics
+        #[arg(long, value_enum, default_value_t = MessageFormat::Human)]
+        message_format: MessageFormat,
     },
     /// Clean the target directory of a Ballerina project
     Clean {
@@ -30,6 +35,31 @@ enum Commands {
         /// If not provided, attempts to clean project in current directory
         path: Option<PathBuf>,
     },
+    /// Apply the compiler's suggested fixes to a file or project
+    Fix {
+        /// Optional path to a .bal file or project directory.
+        /// If not provided, fixes the project in current directory
+        input: Option<PathBuf>,
+
+        /// Print the changes as a unified diff instead of writing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Explain a diagnostic code, such as BCE2001, with examples
+    Explain {
+        /// The code shown in brackets after "error" or "warning"
+        code: String,
+    },
+    /// Measure lexer and parser throughput on a file or project
+    Bench {
+        /// Optional path to a .bal file or project directory.
+        /// If not provided, uses the project in current directory
+        input: Option<PathBuf>,
+
+        /// Number of times to lex and parse every file
+        #[arg(short, long, default_value_t = 10)]
+        iterations: u32,
+    },
 }
 
 fn main() {
@@ -37,8 +67,14 @@ fn main() {
     let config = Config::new(cli.debug);
 
     let result = match cli.command {
-        Commands::Build { input } => commands::build(input, &config),
+        Commands::Build {
+            input,
+            message_format,
+        } => commands::build(input, &config.with_message_format(message_format)),
         Commands::Clean { path } => commands::clean(path, &config),
+        Commands::Fix { input, dry_run } => commands::fix(input, dry_run, &config),
+        Commands::Explain { code } => commands::explain(&code),
+        Commands::Bench { input, iterations } => commands::bench(input, iterations, &config),
     };
 
     if let Err(e) = result {
@@ -50,3 +86,5 @@ fn main() {
 mod commands;
 mod config;
 mod dependency;
+mod diff;
+mod output;
@@ -0,0 +1,223 @@
+//! Machine-readable diagnostics output
+
+use bal_syntax::diagnostic::{Applicability, Diagnostic, Diagnostics, Fix, Label, Severity};
+use bal_syntax::error::Span;
+use bal_syntax::source::SourceDb;
+use clap::ValueEnum;
+use serde_json::{json, Value};
+
+/// How `bal build` prints diagnostics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum MessageFormat {
+    /// Rendered source snippets on stderr
+    #[default]
+    Human,
+    /// One JSON object per diagnostic per line on stdout
+    Json,
+    /// A SARIF 2.1.0 log on stdout
+    Sarif,
+}
+
+/// One JSON object per line. Lines and columns are 1-based, with columns
+/// counted in UTF-16 code units as editors do; byte ranges are 0-based.
+pub fn json_lines(db: &SourceDb, diagnostics: &Diagnostics) -> String {
+    diagnostics
+        .iter()
+        .map(|diagnostic| format!("{}\n", json_diagnostic(db, diagnostic)))
+        .collect()
+}
+
+fn json_diagnostic(db: &SourceDb, diagnostic: &Diagnostic) -> Value {
+    let mut value = json!({
+        "code": diagnostic.code,
+        "severity": diagnostic.severity.to_string(),
+        "message": diagnostic.to_string(),
+        "notes": diagnostic.notes,
+        "help": diagnostic.help,
+        "labels": diagnostic
+            .labels
+            .iter()
+            .map(|label| json_label(db, label))
+            .collect::<Vec<_>>(),
+        "fixes": diagnostic
+            .fixes
+            .iter()
+            .map(|fix| json_fix(db, fix))
+            .collect::<Vec<_>>(),
+    });
+    let location = match &diagnostic.primary {
+        Some(label) => json_label(db, label),
+        None => json!({ "file": null }),
+    };
+    if let (Value::Object(value), Value::Object(location)) = (&mut value, location) {
+        value.extend(location.into_iter().filter(|(key, _)| key != "message"));
+    }
+    value
+}
+
+fn json_label(db: &SourceDb, label: &Label) -> Value {
+    let mut value = json_region(&Region::new(db, label.span));
+    value["message"] = json!(label.message);
+    value
+}
+
+fn json_fix(db: &SourceDb, fix: &Fix) -> Value {
+    json!({
+        "message": fix.message,
+        "applicability": match fix.applicability {
+            Applicability::MachineApplicable => "machine-applicable",
+            Applicability::MaybeIncorrect => "maybe-incorrect",
+        },
+        "edits": fix
+            .edits
+            .iter()
+            .map(|edit| {
+                let mut value = json_region(&Region::new(db, Span::new(fix.file, edit.delete)));
+                value["insert"] = json!(edit.insert);
+                value
+            })
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn json_region(region: &Region) -> Value {
+    json!({
+        "file": region.path,
+        "range": { "start": region.byte_offset, "end": region.byte_offset + region.byte_length },
+        "start": { "line": region.start_line, "column": region.start_column },
+        "end": { "line": region.end_line, "column": region.end_column },
+    })
+}
+
+/// A SARIF 2.1.0 log with one result per diagnostic
+pub fn sarif(db: &SourceDb, diagnostics: &Diagnostics) -> Value {
+    let mut rules: Vec<&str> = diagnostics.iter().map(|d| d.code).collect();
+    rules.sort_unstable();
+    rules.dedup();
+
+    let results: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let mut result = json!({
+                "ruleId": diagnostic.code,
+                "level": match diagnostic.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                    Severity::Hint => "note",
+                },
+                "message": { "text": diagnostic.to_string() },
+                "locations": diagnostic
+                    .primary
+                    .iter()
+                    .map(|label| sarif_location(db, label))
+                    .collect::<Vec<_>>(),
+            });
+            if !diagnostic.labels.is_empty() {
+                result["relatedLocations"] = diagnostic
+                    .labels
+                    .iter()
+                    .enumerate()
+                    .map(|(id, label)| {
+                        let mut location = sarif_location(db, label);
+                        location["id"] = json!(id);
+                        location["message"] = json!({ "text": label.message });
+                        location
+                    })
+                    .collect();
+            }
+            if !diagnostic.fixes.is_empty() {
+                result["fixes"] = diagnostic
+                    .fixes
+                    .iter()
+                    .map(|fix| sarif_fix(db, fix))
+                    .collect();
+            }
+            result
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "bal",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+                }
+            },
+            "columnKind": "utf16CodeUnits",
+            "results": results,
+        }]
+    })
+}
+
+fn sarif_location(db: &SourceDb, label: &Label) -> Value {
+    let region = Region::new(db, label.span);
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": region.path },
+            "region": sarif_region(&region),
+        }
+    })
+}
+
+fn sarif_fix(db: &SourceDb, fix: &Fix) -> Value {
+    json!({
+        "description": { "text": fix.message },
+        "artifactChanges": [{
+            "artifactLocation": { "uri": db.file(fix.file).path },
+            "replacements": fix
+                .edits
+                .iter()
+                .map(|edit| json!({
+                    "deletedRegion": sarif_region(&Region::new(db, Span::new(fix.file, edit.delete))),
+                    "insertedContent": { "text": edit.insert },
+                }))
+                .collect::<Vec<_>>(),
+        }],
+    })
+}
+
+fn sarif_region(region: &Region) -> Value {
+    json!({
+        "startLine": region.start_line,
+        "startColumn": region.start_column,
+        "endLine": region.end_line,
+        "endColumn": region.end_column,
+        "byteOffset": region.byte_offset,
+        "byteLength": region.byte_length,
+    })
+}
+
+/// Where a span is, with 1-based lines and UTF-16 columns
+struct Region<'a> {
+    path: &'a str,
+    byte_offset: usize,
+    byte_length: usize,
+    start_line: u32,
+    start_column: u32,
+    end_line: u32,
+    end_column: u32,
+}
+
+impl<'a> Region<'a> {
+    fn new(db: &'a SourceDb, span: Span) -> Self {
+        let file = db.file(span.file);
+        let start = file.line_index.line_col_utf16(span.range.start());
+        let end = file.line_index.line_col_utf16(span.range.end());
+        Self {
+            path: &file.path,
+            byte_offset: span.start(),
+            byte_length: span.end() - span.start(),
+            start_line: start.line + 1,
+            start_column: start.col + 1,
+            end_line: end.line + 1,
+            end_column: end.col + 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
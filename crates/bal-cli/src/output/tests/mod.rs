@@ -0,0 +1,94 @@
+use super::*;
+use bal_syntax::error::Span;
+use rowan::TextRange;
+
+fn diagnostics(db: &mut SourceDb) -> Diagnostics {
+    let file = db.add_file("main.bal", "function f() { }\nfunction é() { $ }\n");
+    let mut diagnostics = Diagnostics::new();
+    diagnostics.push(
+        Diagnostic::error("BCE1001", "invalid character").with_primary(
+            Span::new(file, TextRange::new(33.into(), 34.into())),
+            "unexpected '$'",
+        ),
+    );
+    diagnostics.push(
+        Diagnostic::warning("BCE3001", "duplicate definition")
+            .with_primary(
+                Span::new(file, TextRange::new(26.into(), 28.into())),
+                "here",
+            )
+            .with_label(
+                Span::new(file, TextRange::new(9.into(), 10.into())),
+                "first",
+            ),
+    );
+    diagnostics.push(Diagnostic::error("BCE0003", "Invalid project: no files"));
+    diagnostics
+}
+
+#[test]
+fn test_json_lines() {
+    let mut db = SourceDb::new();
+    let diagnostics = diagnostics(&mut db);
+    let output = json_lines(&db, &diagnostics);
+    let lines: Vec<Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 3);
+
+    let first = &lines[0];
+    assert_eq!(first["file"], "main.bal");
+    assert_eq!(first["code"], "BCE1001");
+    assert_eq!(first["severity"], "error");
+    assert_eq!(first["message"], "invalid character: unexpected '$'");
+    assert_eq!(first["range"], json!({ "start": 33, "end": 34 }));
+    // 'é' is two bytes but one UTF-16 code unit
+    assert_eq!(first["start"], json!({ "line": 2, "column": 16 }));
+    assert_eq!(first["end"], json!({ "line": 2, "column": 17 }));
+
+    assert_eq!(lines[1]["severity"], "warning");
+    assert_eq!(lines[1]["labels"][0]["message"], "first");
+    assert_eq!(lines[1]["labels"][0]["start"]["column"], 10);
+    assert_eq!(lines[2]["file"], Value::Null);
+}
+
+#[test]
+fn test_sarif() {
+    let mut db = SourceDb::new();
+    let log = sarif(&db, &Diagnostics::new());
+    assert_eq!(log["version"], "2.1.0");
+    assert_eq!(log["runs"][0]["results"], json!([]));
+
+    let diagnostics = diagnostics(&mut db);
+    let log = sarif(&db, &diagnostics);
+    let run = &log["runs"][0];
+    assert_eq!(
+        run["tool"]["driver"]["rules"],
+        json!([{ "id": "BCE0003" }, { "id": "BCE1001" }, { "id": "BCE3001" }])
+    );
+
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "BCE1001");
+    assert_eq!(result["level"], "error");
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "main.bal");
+    assert_eq!(
+        location["region"],
+        json!({
+            "startLine": 2,
+            "startColumn": 16,
+            "endLine": 2,
+            "endColumn": 17,
+            "byteOffset": 33,
+            "byteLength": 1,
+        })
+    );
+
+    assert_eq!(run["results"][1]["level"], "warning");
+    assert_eq!(
+        run["results"][1]["relatedLocations"][0]["message"]["text"],
+        "first"
+    );
+    assert_eq!(run["results"][2]["locations"], json!([]));
+}
@@ -4,6 +4,7 @@
 use bal_ast::SourceFile;
 
 pub struct CodeGenerator {
+    #[allow(dead_code)]
     source_file: SourceFile,
 }
 
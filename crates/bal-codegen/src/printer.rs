@@ -1,9 +1,16 @@
 //! Source code pretty printing
 
 pub struct Printer {
+    #[allow(dead_code)]
     indent: usize,
 }
 
+impl Default for Printer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Printer {
     pub fn new() -> Self {
         Self { indent: 0 }
@@ -33,6 +33,27 @@ mod handlers {
     // TODO: Add handlers module implementation
 }
 
-mod utils {
-    // TODO: Add utils module implementation
+pub mod utils {
+    //! Conversions between compiler offsets and LSP positions
+
+    use bal_syntax::line_index::{LineCol, LineIndex};
+    use rowan::{TextRange, TextSize};
+    use tower_lsp::lsp_types::{Position, Range};
+
+    /// LSP positions count columns in UTF-16 code units
+    pub fn position(index: &LineIndex, offset: TextSize) -> Position {
+        let line_col = index.line_col_utf16(offset);
+        Position::new(line_col.line, line_col.col)
+    }
+
+    pub fn range(index: &LineIndex, range: TextRange) -> Range {
+        Range::new(position(index, range.start()), position(index, range.end()))
+    }
+
+    pub fn offset(index: &LineIndex, position: Position) -> Option<TextSize> {
+        index.offset_utf16(LineCol {
+            line: position.line,
+            col: position.character,
+        })
+    }
 }
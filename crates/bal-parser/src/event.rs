@@ -1,13 +1,82 @@
-//! Parser events for incremental parsing
+//! Parser events, recorded by the grammar and replayed by `Sink`
+//!
+//! The grammar never builds the tree directly. It records where nodes start
+//! and finish and which tokens they hold, using `Marker`s. A completed node
+//! can later be wrapped in a new parent with `CompletedMarker::precede`,
+//! which is how a binary expression takes in its already parsed left
+//! operand. Trivia never appears in the events; the sink decides where each
+//! comment and whitespace token goes.
 
-#[derive(Debug)]
+use crate::Parser;
+use bal_syntax::SyntaxKind;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Event {
-    StartNode {
-        kind: crate::SyntaxKind,
+    /// Start a node. `forward_parent` is the distance to a later `Start`
+    /// whose node encloses this one, set by `CompletedMarker::precede`.
+    Start {
+        kind: SyntaxKind,
+        forward_parent: Option<usize>,
     },
-    AddToken {
-        kind: crate::SyntaxKind,
-        text: String,
+    /// Add the next non-trivia token
+    Token,
+    /// Add the next `parts` tokens, which are adjacent, as one token of
+    /// `kind`, such as `>>` from two `>`s
+    GluedToken {
+        kind: SyntaxKind,
+        parts: usize,
     },
-    FinishNode,
+    Finish,
+    /// A start that was abandoned, or already replayed as a forward parent
+    Tombstone,
+}
+
+/// A node that has been started but not yet completed
+#[must_use]
+pub(crate) struct Marker {
+    pos: usize,
+}
+
+/// A completed node, which can still be given a parent
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CompletedMarker {
+    pos: usize,
+}
+
+impl Parser<'_> {
+    /// Start a node whose kind is decided when it is completed
+    pub(crate) fn start(&mut self) -> Marker {
+        let pos = self.events.len();
+        self.events.push(Event::Tombstone);
+        Marker { pos }
+    }
+}
+
+impl Marker {
+    pub(crate) fn complete(self, p: &mut Parser, kind: SyntaxKind) -> CompletedMarker {
+        p.events[self.pos] = Event::Start {
+            kind,
+            forward_parent: None,
+        };
+        p.events.push(Event::Finish);
+        CompletedMarker { pos: self.pos }
+    }
+
+    /// Drop the node, leaving anything parsed since it started in its parent
+    pub(crate) fn abandon(self, p: &mut Parser) {
+        if self.pos == p.events.len() - 1 {
+            p.events.pop();
+        }
+    }
+}
+
+impl CompletedMarker {
+    /// Start a node that will enclose this one
+    pub(crate) fn precede(self, p: &mut Parser) -> Marker {
+        let parent = p.start();
+        if let Event::Start { forward_parent, .. } = &mut p.events[self.pos] {
+            *forward_parent = Some(parent.pos - self.pos);
+        }
+        parent
+    }
 }
@@ -0,0 +1,48 @@
+//! Parsing a single fragment rather than a whole file, for tools such as a
+//! REPL, a debugger's watch expressions or a code generator
+//!
+//! Each entry point lexes `text` itself and returns a tree rooted at the
+//! fragment's node, along with every lexer and parser diagnostic. Input after
+//! the first complete fragment is reported, and the root is then an `ERROR`
+//! node holding both.
+
+use crate::Parser;
+use bal_syntax::diagnostic::Diagnostic;
+use bal_syntax::lexer::lex;
+use bal_syntax::source::FileId;
+use rowan::GreenNode;
+
+/// Parse one expression, such as `x + 1`
+pub fn parse_expression(file: FileId, text: &str) -> (GreenNode, Vec<Diagnostic>) {
+    parse(file, text, "expression", Parser::parse_expr)
+}
+
+/// Parse one statement, such as `int x = 1;`
+pub fn parse_statement(file: FileId, text: &str) -> (GreenNode, Vec<Diagnostic>) {
+    parse(file, text, "statement", Parser::parse_statement)
+}
+
+/// Parse one type descriptor, such as `int`
+pub fn parse_type_desc(file: FileId, text: &str) -> (GreenNode, Vec<Diagnostic>) {
+    parse(file, text, "type descriptor", Parser::parse_type_desc)
+}
+
+/// Parse one import or module-level declaration
+pub fn parse_module_member(file: FileId, text: &str) -> (GreenNode, Vec<Diagnostic>) {
+    parse(file, text, "module member", Parser::parse_module_member)
+}
+
+fn parse<'a>(
+    file: FileId,
+    text: &'a str,
+    fragment: &str,
+    rule: impl FnOnce(&mut Parser<'a>) -> crate::Result<()>,
+) -> (GreenNode, Vec<Diagnostic>) {
+    let lexed = lex(file, text);
+    let mut diagnostics = lexed.diagnostics;
+    let mut parser = Parser::new(file, text, lexed.tokens);
+    parser.parse_fragment(fragment, rule);
+    let green = parser.build_tree();
+    diagnostics.extend(parser.diagnostics.into_vec());
+    (green, diagnostics)
+}
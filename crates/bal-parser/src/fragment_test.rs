@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod tests {
+    use crate::fragment::{
+        parse_expression, parse_module_member, parse_statement, parse_type_desc,
+    };
+    use bal_syntax::diagnostic::Diagnostic;
+    use bal_syntax::source::FileId;
+    use bal_syntax::BallerinaLanguage;
+    use rowan::{GreenNode, SyntaxNode};
+
+    /// The tree followed by its diagnostics
+    fn show((green, diagnostics): (GreenNode, Vec<Diagnostic>)) -> String {
+        let mut out = format!("{:#?}", SyntaxNode::<BallerinaLanguage>::new_root(green));
+        for diagnostic in diagnostics {
+            out.push_str(&format!("{}\n", diagnostic));
+        }
+        out
+    }
+
+    #[test]
+    fn test_expression() {
+        insta::assert_snapshot!(show(parse_expression(
+            FileId::default(),
+            " x + 1 // done\n"
+        )));
+    }
+
+    #[test]
+    fn test_expression_with_trailing_input() {
+        insta::assert_snapshot!(show(parse_expression(FileId::default(), "x + 1 y z")));
+    }
+
+    #[test]
+    fn test_statement() {
+        insta::assert_snapshot!(show(parse_statement(FileId::default(), "int x = f(1);")));
+    }
+
+    #[test]
+    fn test_two_statements_are_trailing_input() {
+        let (green, diagnostics) = parse_statement(FileId::default(), "break; continue;");
+        let root = SyntaxNode::<BallerinaLanguage>::new_root(green);
+        assert_eq!(root.kind(), bal_syntax::SyntaxKind::ERROR);
+        assert_eq!(root.text().to_string(), "break; continue;");
+        let messages: Vec<_> = diagnostics.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            ["unexpected trailing input: expected end of input after statement"]
+        );
+    }
+
+    #[test]
+    fn test_type_desc() {
+        insta::assert_snapshot!(show(parse_type_desc(FileId::default(), "boolean")));
+    }
+
+    #[test]
+    fn test_module_member() {
+        insta::assert_snapshot!(show(parse_module_member(
+            FileId::default(),
+            "# Docs\npublic function f() { }\n"
+        )));
+    }
+
+    #[test]
+    fn test_empty_input() {
+        insta::assert_snapshot!(show(parse_expression(FileId::default(), "  ")));
+    }
+
+    #[test]
+    fn test_lexer_errors_are_reported() {
+        let (_, diagnostics) = parse_expression(FileId::default(), "\"open");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].code,
+            bal_syntax::diagnostic::codes::UNTERMINATED_STRING
+        );
+    }
+}
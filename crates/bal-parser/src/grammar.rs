@@ -1,171 +1,598 @@
 //! Grammar rules for Ballerina parser
 
 use super::Parser;
+use crate::event::{CompletedMarker, Event};
 use bal_syntax::error::{ParserError, Span};
-use bal_syntax::{BallerinaLanguage, SyntaxKind};
-use rowan::Language;
+use bal_syntax::SyntaxKind;
+use rowan::TextRange;
+
+mod expressions;
+mod types;
+
+/// How a parameter kind is named in messages
+fn param_description(kind: SyntaxKind) -> &'static str {
+    match kind {
+        SyntaxKind::DEFAULTABLE_PARAM => "defaultable parameter",
+        SyntaxKind::REST_PARAM => "rest parameter",
+        SyntaxKind::INCLUDED_RECORD_PARAM => "included record parameter",
+        _ => "required parameter",
+    }
+}
 
-impl Parser {
+impl Parser<'_> {
     pub(crate) fn parse_module_part(&mut self) -> std::result::Result<(), ParserError> {
-        self.builder
-            .start_node(BallerinaLanguage::kind_to_raw(SyntaxKind::MODULE_PART));
+        self.node(SyntaxKind::MODULE_PART, |p| {
+            while !p.at_end() {
+                let start = p.cursor;
+                if let Err(e) = p.parse_module_member() {
+                    p.report(e);
+                    p.recover_declaration(start);
+                }
+            }
+            Ok(())
+        })
+    }
 
-        // Parse imports
-        while self.at(SyntaxKind::IMPORT_KW) {
-            self.parse_import_decl()?;
+    /// Parse input that should hold exactly one `fragment` with `rule`. The
+    /// root is the fragment's node, or an `ERROR` node holding it along with
+    /// any input that could not be parsed.
+    pub(crate) fn parse_fragment(
+        &mut self,
+        fragment: &str,
+        rule: impl FnOnce(&mut Self) -> Result<(), ParserError>,
+    ) {
+        let root = self.start();
+        let result = rule(self);
+        let complete = result.is_ok() && self.at_end();
+        match result {
+            Err(e) => self.report(e),
+            Ok(()) if !self.at_end() => {
+                let end = self
+                    .tokens
+                    .iter()
+                    .rev()
+                    .find(|token| !token.kind.is_trivia())
+                    .map(|token| token.range.end())
+                    .unwrap_or_default();
+                let start = self.current_span().range.start();
+                self.diagnostics.push(ParserError::TrailingInput {
+                    fragment: fragment.to_string(),
+                    span: Span::new(self.file, TextRange::new(start, end)),
+                });
+            }
+            Ok(()) => {}
         }
+        while self.bump().is_ok() {}
 
-        // Parse function declarations
-        while !self.at_end() && self.at_function_start() {
-            self.parse_module_decl()?;
+        if complete {
+            root.abandon(self);
+        } else {
+            root.complete(self, SyntaxKind::ERROR);
         }
+    }
 
-        self.builder.finish_node();
-        Ok(())
+    /// An import or a declaration
+    pub(crate) fn parse_module_member(&mut self) -> Result<(), ParserError> {
+        match self.peek_kind() {
+            Some(SyntaxKind::IMPORT_KW) => self.parse_import_decl(),
+            _ => self.parse_module_decl(),
+        }
     }
 
     fn parse_import_decl(&mut self) -> Result<(), ParserError> {
-        self.builder
-            .start_node(BallerinaLanguage::kind_to_raw(SyntaxKind::IMPORT_DECL));
+        self.node(SyntaxKind::IMPORT_DECL, |p| {
+            p.expect(SyntaxKind::IMPORT_KW)?;
+            if let Err(e) = p.parse_import_name() {
+                p.report(e);
+                p.recover_until(&[SyntaxKind::SEMICOLON]);
+                if p.at(SyntaxKind::SEMICOLON) {
+                    p.bump()?;
+                }
+                return Ok(());
+            }
+            p.expect_semicolon("import statement")
+        })
+    }
 
-        self.expect(SyntaxKind::IMPORT_KW)?;
-        self.expect(SyntaxKind::IDENTIFIER)?;
-        self.expect(SyntaxKind::SLASH)?;
-        self.expect(SyntaxKind::IDENTIFIER)?;
-        self.expect(SyntaxKind::SEMICOLON)
-            .map_err(|_| ParserError::MissingToken {
-                expected: "semicolon".to_string(),
-                after: "import statement".to_string(),
-                span: self.current_span(),
+    /// `[org /] module.name [as prefix]`; without an org the module is one
+    /// of the current package's
+    fn parse_import_name(&mut self) -> Result<(), ParserError> {
+        if self.nth(1) == Some(SyntaxKind::SLASH) {
+            self.node(SyntaxKind::IMPORT_ORG_NAME, |p| {
+                p.expect(SyntaxKind::IDENTIFIER)?;
+                p.expect(SyntaxKind::SLASH)
             })?;
-
-        self.builder.finish_node();
+        }
+        self.node(SyntaxKind::MODULE_NAME, |p| {
+            p.expect(SyntaxKind::IDENTIFIER)?;
+            while p.at(SyntaxKind::DOT) {
+                p.bump()?;
+                p.expect(SyntaxKind::IDENTIFIER)?;
+            }
+            Ok(())
+        })?;
+        if self.at(SyntaxKind::AS_KW) {
+            // `_` is an identifier, so it needs no special case here
+            self.node(SyntaxKind::IMPORT_PREFIX, |p| {
+                p.bump()?;
+                p.expect(SyntaxKind::IDENTIFIER)
+            })?;
+        }
         Ok(())
     }
 
     fn parse_module_decl(&mut self) -> Result<(), ParserError> {
-        // Currently only function definitions are supported
-        self.parse_function_def()?;
-        Ok(())
+        // Any declaration may be public, so look past `public`
+        let kind = match self.peek_kind() {
+            Some(SyntaxKind::PUBLIC_KW) => self.nth(1),
+            kind => kind,
+        };
+        match kind {
+            Some(SyntaxKind::FUNCTION_KW) => self.parse_function_def(),
+            Some(SyntaxKind::CONST_KW) => self.parse_const_decl(),
+            Some(SyntaxKind::TYPE_KW) => self.parse_type_definition(),
+            Some(
+                SyntaxKind::FINAL_KW
+                | SyntaxKind::CONFIGURABLE_KW
+                | SyntaxKind::IDENTIFIER
+                | SyntaxKind::L_PAREN,
+            ) => self.parse_module_var_decl(),
+            _ if self.at_type_desc_start() => self.parse_module_var_decl(),
+            _ => Err(self.unexpected("declaration")),
+        }
     }
 
-    fn parse_function_def(&mut self) -> Result<(), ParserError> {
-        self.builder
-            .start_node(BallerinaLanguage::kind_to_raw(SyntaxKind::FUNCTION_DEF));
-
-        // Optional public modifier
-        if self.at(SyntaxKind::PUBLIC_KW) {
-            self.bump()?;
-        }
+    /// `const [type-desc] name = expr;`
+    fn parse_const_decl(&mut self) -> Result<(), ParserError> {
+        self.node(SyntaxKind::CONST_DECL, |p| {
+            if p.at(SyntaxKind::PUBLIC_KW) {
+                p.bump()?;
+            }
+            p.expect(SyntaxKind::CONST_KW)?;
+            // Without a type, the name follows `const` directly
+            if !(p.at(SyntaxKind::IDENTIFIER) && p.nth(1) == Some(SyntaxKind::ASSIGN)) {
+                p.parse_type_desc()?;
+            }
+            p.expect(SyntaxKind::IDENTIFIER)?;
+            p.expect(SyntaxKind::ASSIGN)?;
+            p.parse_expr()?;
+            p.expect_semicolon("constant declaration")
+        })
+    }
 
-        self.expect(SyntaxKind::FUNCTION_KW)?;
-        self.expect(SyntaxKind::IDENTIFIER)?;
-        self.parse_signature()?;
-        self.parse_stmt_block()?;
+    /// `[final | configurable] type-desc name = expr;`, where a configurable
+    /// variable may write `?` for a value that must come from configuration
+    fn parse_module_var_decl(&mut self) -> Result<(), ParserError> {
+        self.node(SyntaxKind::MODULE_VAR_DECL, |p| {
+            if p.at(SyntaxKind::PUBLIC_KW) {
+                p.bump()?;
+            }
+            let configurable = p.at(SyntaxKind::CONFIGURABLE_KW);
+            if configurable || p.at(SyntaxKind::FINAL_KW) {
+                p.bump()?;
+            }
+            p.parse_type_desc()?;
+            p.expect(SyntaxKind::IDENTIFIER)?;
+            p.expect(SyntaxKind::ASSIGN)?;
+            if configurable && p.at(SyntaxKind::QUESTION) {
+                p.bump()?;
+            } else {
+                p.parse_expr()?;
+            }
+            p.expect_semicolon("variable declaration")
+        })
+    }
 
-        self.builder.finish_node();
-        Ok(())
+    /// `type Name type-desc;`
+    fn parse_type_definition(&mut self) -> Result<(), ParserError> {
+        self.node(SyntaxKind::TYPE_DEFINITION, |p| {
+            if p.at(SyntaxKind::PUBLIC_KW) {
+                p.bump()?;
+            }
+            p.expect(SyntaxKind::TYPE_KW)?;
+            p.expect(SyntaxKind::IDENTIFIER)?;
+            p.parse_type_desc()?;
+            p.expect_semicolon("type definition")
+        })
     }
 
-    fn parse_signature(&mut self) -> Result<(), ParserError> {
-        self.builder
-            .start_node(BallerinaLanguage::kind_to_raw(SyntaxKind::SIGNATURE));
+    fn parse_function_def(&mut self) -> Result<(), ParserError> {
+        self.node(SyntaxKind::FUNCTION_DEF, |p| {
+            // Optional public modifier
+            if p.at(SyntaxKind::PUBLIC_KW) {
+                p.bump()?;
+            }
 
-        self.expect(SyntaxKind::L_PAREN)?;
-        if !self.at(SyntaxKind::R_PAREN) {
-            self.parse_param_list()?;
-        }
-        self.expect(SyntaxKind::R_PAREN)?;
+            p.expect(SyntaxKind::FUNCTION_KW)?;
+            p.expect(SyntaxKind::IDENTIFIER)?;
+            p.parse_signature()?;
+            p.parse_stmt_block()
+        })
+    }
 
-        if self.at(SyntaxKind::RETURNS_KW) {
-            self.bump()?;
-            self.parse_type_desc()?;
-        }
+    fn parse_signature(&mut self) -> Result<(), ParserError> {
+        self.node(SyntaxKind::SIGNATURE, |p| {
+            p.expect(SyntaxKind::L_PAREN)?;
+            if !p.at(SyntaxKind::R_PAREN) {
+                p.parse_param_list()?;
+            }
+            if let Err(e) = p.expect(SyntaxKind::R_PAREN) {
+                p.report(e);
+                p.recover_until(&[
+                    SyntaxKind::R_PAREN,
+                    SyntaxKind::RETURNS_KW,
+                    SyntaxKind::L_BRACE,
+                ]);
+                if p.at(SyntaxKind::R_PAREN) {
+                    p.bump()?;
+                }
+            }
 
-        self.builder.finish_node();
-        Ok(())
+            if p.at(SyntaxKind::RETURNS_KW) {
+                p.bump()?;
+                p.parse_type_desc()?;
+            }
+            Ok(())
+        })
     }
 
     fn parse_param_list(&mut self) -> Result<(), ParserError> {
-        self.builder
-            .start_node(BallerinaLanguage::kind_to_raw(SyntaxKind::PARAM_LIST));
-
-        loop {
-            self.parse_param()?;
-            if !self.at(SyntaxKind::COMMA) {
-                break;
+        self.node(SyntaxKind::PARAM_LIST, |p| {
+            // The latest parameter that limits what may follow it
+            let mut previous = None;
+            loop {
+                let span = p.current_span();
+                match p.parse_param() {
+                    Ok(kind) => {
+                        if let Some((previous_kind, previous_span)) = previous {
+                            p.check_param_order(kind, previous_kind, previous_span, span);
+                        }
+                        if kind != SyntaxKind::PARAM && kind != SyntaxKind::INCLUDED_RECORD_PARAM {
+                            previous = Some((kind, span));
+                        }
+                    }
+                    Err(e) => {
+                        p.report(e);
+                        p.recover_until(&[
+                            SyntaxKind::COMMA,
+                            SyntaxKind::R_PAREN,
+                            SyntaxKind::RETURNS_KW,
+                            SyntaxKind::L_BRACE,
+                        ]);
+                    }
+                }
+                if !p.at(SyntaxKind::COMMA) {
+                    break;
+                }
+                p.bump()?; // Consume comma
             }
-            self.bump()?; // Consume comma
+            Ok(())
+        })
+    }
+
+    /// Required parameters come before defaultable ones, and the rest
+    /// parameter comes last. Included record parameters may follow
+    /// defaultable ones, as in `string msg, error? e = (), *KeyValues kvs`.
+    /// Reported without stopping, since the list itself parsed.
+    fn check_param_order(
+        &mut self,
+        kind: SyntaxKind,
+        previous_kind: SyntaxKind,
+        previous: Span,
+        span: Span,
+    ) {
+        let misplaced = previous_kind == SyntaxKind::REST_PARAM
+            || (previous_kind == SyntaxKind::DEFAULTABLE_PARAM && kind == SyntaxKind::PARAM);
+        if misplaced {
+            self.diagnostics.push(ParserError::ParameterOrder {
+                kind: param_description(kind).to_string(),
+                previous_kind: param_description(previous_kind).to_string(),
+                previous,
+                span,
+            });
         }
+    }
 
-        self.builder.finish_node();
-        Ok(())
+    /// `int x`, `int x = 10`, `int... xs` or `*Options opts`, returning
+    /// which of them it was
+    fn parse_param(&mut self) -> Result<SyntaxKind, ParserError> {
+        if self.at(SyntaxKind::STAR) {
+            return self
+                .node(SyntaxKind::INCLUDED_RECORD_PARAM, |p| {
+                    p.bump()?;
+                    p.parse_type_desc()?;
+                    p.expect(SyntaxKind::IDENTIFIER)
+                })
+                .map(|()| SyntaxKind::INCLUDED_RECORD_PARAM);
+        }
+
+        // The kind is only known once the type has been parsed
+        let marker = self.start();
+        let mut kind = SyntaxKind::PARAM;
+        let result = self.parse_param_after_type(&mut kind);
+        marker.complete(self, kind);
+        result.map(|()| kind)
     }
 
-    fn parse_param(&mut self) -> Result<(), ParserError> {
-        self.builder
-            .start_node(BallerinaLanguage::kind_to_raw(SyntaxKind::PARAM));
+    fn parse_param_after_type(&mut self, kind: &mut SyntaxKind) -> Result<(), ParserError> {
         self.parse_type_desc()?;
+        if self.at(SyntaxKind::ELLIPSIS) {
+            *kind = SyntaxKind::REST_PARAM;
+            self.bump()?;
+        }
         self.expect(SyntaxKind::IDENTIFIER)?;
-        self.builder.finish_node();
+        if *kind == SyntaxKind::PARAM && self.at(SyntaxKind::ASSIGN) {
+            *kind = SyntaxKind::DEFAULTABLE_PARAM;
+            self.bump()?;
+            self.parse_expr()?;
+        }
         Ok(())
     }
 
-    fn parse_type_desc(&mut self) -> Result<(), ParserError> {
-        self.builder
-            .start_node(BallerinaLanguage::kind_to_raw(SyntaxKind::TYPE_DESC));
-        self.expect_one_of(&[SyntaxKind::INT_KW, SyntaxKind::BOOLEAN_KW])?;
-        self.builder.finish_node();
-        Ok(())
+    pub(crate) fn parse_stmt_block(&mut self) -> Result<(), ParserError> {
+        self.node(SyntaxKind::STMT_BLOCK, |p| {
+            p.expect(SyntaxKind::L_BRACE)?;
+
+            // A declaration keyword means this block was never closed; leave
+            // it for the module to parse
+            while !p.at(SyntaxKind::R_BRACE) && !p.at_end() && !p.at_declaration_start_in_block() {
+                let start = p.cursor;
+                if let Err(e) = p.parse_statement() {
+                    p.report(e);
+                    p.recover_statement(start);
+                }
+            }
+
+            p.expect(SyntaxKind::R_BRACE)
+        })
+    }
+
+    pub(crate) fn parse_statement(&mut self) -> Result<(), ParserError> {
+        match self.peek_kind() {
+            _ if self.at_local_var_decl() => self.parse_local_var_decl(),
+            Some(SyntaxKind::IDENTIFIER) => match self.nth(1) {
+                Some(SyntaxKind::ASSIGN) => self.parse_assign_stmt(),
+                _ => self.node(SyntaxKind::FUNCTION_CALL_STMT, |p| {
+                    p.parse_function_call()?;
+                    p.expect_semicolon("statement")
+                }),
+            },
+            Some(SyntaxKind::RETURN_KW) => self.node(SyntaxKind::RETURN_STMT, |p| {
+                p.bump()?;
+                if !p.at(SyntaxKind::SEMICOLON) {
+                    p.parse_expr()?;
+                }
+                p.expect_semicolon("statement")
+            }),
+            Some(SyntaxKind::IF_KW) => self.parse_if_else_stmt(),
+            Some(SyntaxKind::WHILE_KW) => self.node(SyntaxKind::WHILE_STMT, |p| {
+                p.bump()?;
+                p.parse_condition()?;
+                p.parse_stmt_block()
+            }),
+            Some(SyntaxKind::BREAK_KW) => self.node(SyntaxKind::BREAK_STMT, |p| {
+                p.bump()?;
+                p.expect_semicolon("statement")
+            }),
+            Some(SyntaxKind::CONTINUE_KW) => self.node(SyntaxKind::CONTINUE_STMT, |p| {
+                p.bump()?;
+                p.expect_semicolon("statement")
+            }),
+            _ => Err(self.unexpected("statement")),
+        }
     }
 
-    fn parse_stmt_block(&mut self) -> Result<(), ParserError> {
-        self.builder
-            .start_node(BallerinaLanguage::kind_to_raw(SyntaxKind::STMT_BLOCK));
+    /// Whether a statement starts with a type, or with `final` before one.
+    /// A name can start a type too, as in `Point p = ...`, so look past it
+    /// to tell it from a call or an assignment.
+    fn at_local_var_decl(&self) -> bool {
+        match self.peek_kind() {
+            Some(SyntaxKind::L_PAREN | SyntaxKind::FINAL_KW) => true,
+            Some(SyntaxKind::IDENTIFIER) => {
+                let next = if self.nth(1) == Some(SyntaxKind::COLON) {
+                    3
+                } else {
+                    1
+                };
+                matches!(
+                    self.nth(next),
+                    Some(
+                        SyntaxKind::IDENTIFIER
+                            | SyntaxKind::QUESTION
+                            | SyntaxKind::PIPE
+                            | SyntaxKind::L_BRACKET
+                    )
+                )
+            }
+            _ => self.at_type_desc_start(),
+        }
+    }
 
-        self.expect(SyntaxKind::L_BRACE)?;
+    fn parse_local_var_decl(&mut self) -> Result<(), ParserError> {
+        self.node(SyntaxKind::LOCAL_VAR_DECL_STMT, |p| {
+            if p.at(SyntaxKind::FINAL_KW) {
+                p.bump()?;
+            }
+            p.parse_type_desc()?;
+            p.expect(SyntaxKind::IDENTIFIER)?;
+            p.expect(SyntaxKind::ASSIGN)?;
+            p.parse_expr()?;
+            p.expect_semicolon("statement")
+        })
+    }
+
+    fn parse_assign_stmt(&mut self) -> Result<(), ParserError> {
+        self.node(SyntaxKind::ASSIGN_STMT, |p| {
+            p.expect(SyntaxKind::IDENTIFIER)?;
+            p.expect(SyntaxKind::ASSIGN)?;
+            p.parse_expr()?;
+            p.expect_semicolon("statement")
+        })
+    }
 
-        while !self.at(SyntaxKind::R_BRACE) && !self.at_end() {
-            match self.parse_statement() {
-                Ok(_) => {}
-                Err(e) => {
-                    // Report error but continue parsing
-                    eprintln!("Error in statement: {}", e);
-                    self.synchronize();
+    /// `if cond { } else if cond { } else { }`; each `else if` nests another
+    /// `IF_ELSE_STMT` in the `else` branch
+    fn parse_if_else_stmt(&mut self) -> Result<(), ParserError> {
+        self.node(SyntaxKind::IF_ELSE_STMT, |p| {
+            p.expect(SyntaxKind::IF_KW)?;
+            p.parse_condition()?;
+            p.parse_stmt_block()?;
+            if p.at(SyntaxKind::ELSE_KW) {
+                p.bump()?;
+                if p.at(SyntaxKind::IF_KW) {
+                    p.parse_if_else_stmt()?;
+                } else {
+                    p.parse_stmt_block()?;
                 }
             }
-        }
+            Ok(())
+        })
+    }
 
-        self.expect(SyntaxKind::R_BRACE)?;
-        self.builder.finish_node();
-        Ok(())
+    /// Skip the rest of a statement that failed to parse, keeping the skipped
+    /// tokens in an `ERROR` node. Stops after a `;`, or before a `}` closing
+    /// the block, a keyword starting the next statement or any declaration.
+    fn recover_statement(&mut self, start: usize) {
+        let marker = self.start();
+        let mut skipped = false;
+        let mut depth = 0usize;
+        while let Some(kind) = self.peek_kind() {
+            let moved = skipped || self.cursor > start;
+            match kind {
+                SyntaxKind::R_BRACE if depth == 0 => break,
+                _ if self.at_declaration_start_in_block() => break,
+                SyntaxKind::IF_KW
+                | SyntaxKind::WHILE_KW
+                | SyntaxKind::RETURN_KW
+                | SyntaxKind::BREAK_KW
+                | SyntaxKind::CONTINUE_KW
+                | SyntaxKind::FINAL_KW
+                    if depth == 0 && moved =>
+                {
+                    break
+                }
+                SyntaxKind::L_BRACE | SyntaxKind::L_BRACE_PIPE => depth += 1,
+                // A stray `|}` is skipped like any other token
+                SyntaxKind::R_BRACE | SyntaxKind::R_BRACE_PIPE => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            skipped = true;
+            let _ = self.bump();
+            if kind == SyntaxKind::SEMICOLON && depth == 0 {
+                break;
+            }
+        }
+        if skipped {
+            marker.complete(self, SyntaxKind::ERROR);
+        } else {
+            marker.abandon(self);
+        }
     }
 
-    fn parse_statement(&mut self) -> Result<(), ParserError> {
-        self.builder
-            .start_node(BallerinaLanguage::kind_to_raw(SyntaxKind::STATEMENT));
+    /// Skip the rest of a declaration that failed to parse, keeping the
+    /// skipped tokens in an `ERROR` node. Stops after a `;` or `}` outside any
+    /// braces, or before a keyword starting the next declaration.
+    fn recover_declaration(&mut self, start: usize) {
+        let marker = self.start();
+        let mut skipped = false;
+        let mut depth = 0usize;
+        while let Some(kind) = self.peek_kind() {
+            // Skip at least one token, so a bad `public` is not retried
+            let moved = skipped || self.cursor > start;
+            if moved && self.at_declaration_start() {
+                break;
+            }
+            match kind {
+                SyntaxKind::L_BRACE | SyntaxKind::L_BRACE_PIPE => depth += 1,
+                SyntaxKind::R_BRACE | SyntaxKind::R_BRACE_PIPE => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            skipped = true;
+            let _ = self.bump();
+            if depth == 0
+                && matches!(
+                    kind,
+                    SyntaxKind::SEMICOLON | SyntaxKind::R_BRACE | SyntaxKind::R_BRACE_PIPE
+                )
+            {
+                break;
+            }
+        }
+        if skipped {
+            marker.complete(self, SyntaxKind::ERROR);
+        } else {
+            marker.abandon(self);
+        }
+    }
 
-        // For now, just expect a semicolon-terminated statement
-        while !self.at(SyntaxKind::SEMICOLON) && !self.at_end() {
-            self.bump()?;
+    /// Skip tokens into an `ERROR` node until one in `recovery`, or one
+    /// starting a declaration, so a bad region never hides what follows it
+    fn recover_until(&mut self, recovery: &[SyntaxKind]) {
+        let marker = self.start();
+        let mut skipped = false;
+        while let Some(kind) = self.peek_kind() {
+            if recovery.contains(&kind) || self.at_declaration_start() {
+                break;
+            }
+            skipped = true;
+            let _ = self.bump();
         }
-        self.expect(SyntaxKind::SEMICOLON)?;
+        if skipped {
+            marker.complete(self, SyntaxKind::ERROR);
+        } else {
+            marker.abandon(self);
+        }
+    }
 
-        self.builder.finish_node();
-        Ok(())
+    /// Record an error that parsing recovers from
+    fn report(&mut self, error: ParserError) {
+        // The lexer already reported invalid input
+        if self.peek_kind() != Some(SyntaxKind::ERROR) {
+            self.diagnostics.push(error);
+        }
     }
 
     // Helper methods
     fn at(&self, kind: SyntaxKind) -> bool {
         let pos = self.next_non_trivia();
-        pos < self.tokens.len() && self.tokens[pos].0 == kind
+        pos < self.tokens.len() && self.tokens[pos].kind == kind
+    }
+
+    /// The kind of the `n`th non-trivia token ahead, counting from zero
+    fn nth(&self, n: usize) -> Option<SyntaxKind> {
+        self.tokens[self.cursor..]
+            .iter()
+            .filter(|token| !token.kind.is_trivia())
+            .nth(n)
+            .map(|token| token.kind)
+    }
+
+    /// Whether the next tokens are `kinds` with no trivia between them
+    fn at_adjacent(&self, kinds: &[SyntaxKind]) -> bool {
+        let pos = self.next_non_trivia();
+        self.tokens
+            .get(pos..pos + kinds.len())
+            .is_some_and(|tokens| {
+                tokens
+                    .iter()
+                    .map(|token| token.kind)
+                    .eq(kinds.iter().copied())
+            })
+    }
+
+    /// Consume the next `parts` tokens, which must be adjacent, as one
+    /// token of `kind`
+    fn bump_glued(&mut self, kind: SyntaxKind, parts: usize) {
+        let pos = self.next_non_trivia();
+        self.events.push(Event::GluedToken { kind, parts });
+        self.cursor = pos + parts;
     }
 
     fn next_non_trivia(&self) -> usize {
         let mut pos = self.cursor;
         while pos < self.tokens.len() {
-            let kind = self.tokens[pos].0;
-            if !matches!(kind, SyntaxKind::WHITESPACE | SyntaxKind::COMMENT) {
+            let kind = self.tokens[pos].kind;
+            if !kind.is_trivia() {
                 break;
             }
             pos += 1;
@@ -173,40 +600,29 @@ impl Parser {
         pos
     }
 
-    // fn skip_trivia(&mut self) {
-    //     while let Some(kind) = self.peek_kind() {
-    //         if matches!(kind, SyntaxKind::WHITESPACE | SyntaxKind::COMMENT) {
-    //             self.cursor += 1;
-    //         } else {
-    //             break;
-    //         }
-    //     }
-    // }
-
-    fn at_end(&self) -> bool {
+    pub(crate) fn at_end(&self) -> bool {
         self.peek_kind().is_none()
     }
 
     fn peek_kind(&self) -> Option<SyntaxKind> {
         let pos = self.next_non_trivia();
         if pos < self.tokens.len() {
-            Some(self.tokens[pos].0)
+            Some(self.tokens[pos].kind)
         } else {
             None
         }
     }
 
     fn bump(&mut self) -> Result<(), ParserError> {
-        if self.cursor < self.tokens.len() {
-            let (kind, text, _) = self.tokens[self.cursor].clone();
-            self.builder
-                .token(BallerinaLanguage::kind_to_raw(kind), &text);
-            self.cursor += 1;
+        let pos = self.next_non_trivia();
+        if pos < self.tokens.len() {
+            self.events.push(Event::Token);
+            self.cursor = pos + 1;
             Ok(())
         } else {
             Err(ParserError::UnexpectedToken {
                 expected: vec!["token".to_string()],
-                found: "end of file".to_string(),
+                found: SyntaxKind::EOF.to_string(),
                 span: self.current_span(),
             })
         }
@@ -217,133 +633,121 @@ impl Parser {
             self.bump()?;
             Ok(())
         } else {
-            let err = ParserError::UnexpectedToken {
-                expected: vec![format!("{:?}", kind)],
-                found: format!("{:?}", self.peek_kind().unwrap_or(SyntaxKind::EOF)),
-                span: self.current_span(),
-            };
-
-            // Mark error in syntax tree
-            self.builder
-                .start_node(BallerinaLanguage::kind_to_raw(SyntaxKind::ERROR));
-            self.builder.finish_node();
-
-            // Recover at next statement
-            self.synchronize();
+            Err(self.unexpected(&kind.to_string()))
+        }
+    }
 
-            Err(err)
+    /// Expect the `;` ending a statement or declaration, described by
+    /// `after`. When the next token starts a new line, closes the block or
+    /// there is none, the `;` is missing rather than the token unexpected:
+    /// that is reported with a fix, and parsing carries on.
+    fn expect_semicolon(&mut self, after: &str) -> Result<(), ParserError> {
+        if self.at(SyntaxKind::SEMICOLON) {
+            return self.bump();
+        }
+        let on_new_line = self.tokens[self.cursor..self.next_non_trivia()]
+            .iter()
+            .any(|token| token.kind == SyntaxKind::NEWLINE);
+        let closes = self.at(SyntaxKind::R_BRACE) || self.at(SyntaxKind::R_BRACE_PIPE);
+        if !(on_new_line || closes || self.at_end()) {
+            return Err(self.unexpected(&SyntaxKind::SEMICOLON.to_string()));
         }
+        self.report(ParserError::MissingToken {
+            expected: SyntaxKind::SEMICOLON,
+            after: after.to_string(),
+            span: self.previous_end_span(),
+        });
+        Ok(())
     }
 
     fn expect_one_of(&mut self, kinds: &[SyntaxKind]) -> Result<(), ParserError> {
-        if let Some(current) = self.peek_kind() {
-            if kinds.contains(&current) {
-                self.bump()?;
-                Ok(())
-            } else {
-                let err = ParserError::UnexpectedToken {
-                    expected: kinds.iter().map(|&k| format!("{:?}", k)).collect(),
-                    found: format!("{:?}", current),
-                    span: self.current_span(),
-                };
-
-                self.builder
-                    .start_node(BallerinaLanguage::kind_to_raw(SyntaxKind::ERROR));
-                self.builder.finish_node();
-
-                self.recover_until(&[SyntaxKind::SEMICOLON, SyntaxKind::R_BRACE]);
-
-                Err(err)
-            }
-        } else {
-            Err(ParserError::UnexpectedToken {
-                expected: kinds.iter().map(|&k| format!("{:?}", k)).collect(),
-                found: "end of file".to_string(),
+        match self.peek_kind() {
+            Some(current) if kinds.contains(&current) => self.bump(),
+            _ => Err(ParserError::UnexpectedToken {
+                expected: kinds.iter().map(SyntaxKind::to_string).collect(),
+                found: self.peek_kind().unwrap_or(SyntaxKind::EOF).to_string(),
                 span: self.current_span(),
-            })
+            }),
         }
     }
 
-    fn current_span(&self) -> Span {
-        let pos = self.next_non_trivia();
-        if pos < self.tokens.len() {
-            let (_, _, span) = &self.tokens[pos];
-            Span {
-                file: self.file.clone(),
-                ..span.clone()
-            }
-        } else {
-            // Create EOF span
-            Span {
-                file: self.file.clone(),
-                start: self.tokens.len(),
-                end: self.tokens.len(),
-                line: self.tokens.last().map(|(_, _, s)| s.line).unwrap_or(1),
-                column: self
-                    .tokens
-                    .last()
-                    .map(|(_, _, s)| s.column + 1)
-                    .unwrap_or(0),
-                line_content: None,
-            }
+    /// An error for finding the current token where `expected` should be
+    fn unexpected(&self, expected: &str) -> ParserError {
+        ParserError::UnexpectedToken {
+            expected: vec![expected.to_string()],
+            found: self.peek_kind().unwrap_or(SyntaxKind::EOF).to_string(),
+            span: self.current_span(),
         }
     }
 
-    fn at_function_start(&self) -> bool {
-        self.at(SyntaxKind::FUNCTION_KW) || self.at(SyntaxKind::PUBLIC_KW)
+    /// Parse with `f` inside a new node, which is completed even if `f` fails
+    fn node(
+        &mut self,
+        kind: SyntaxKind,
+        f: impl FnOnce(&mut Self) -> Result<(), ParserError>,
+    ) -> Result<(), ParserError> {
+        self.node_marker(kind, f).map(|_| ())
     }
 
-    // Add this helper method for error recovery
-    fn recover_until(&mut self, sync_tokens: &[SyntaxKind]) {
-        while let Some(kind) = self.peek_kind() {
-            if sync_tokens.contains(&kind) || kind == SyntaxKind::NEWLINE {
-                break;
-            }
-            // Skip the current token
-            let _ = self.bump();
-        }
+    /// Like `node`, returning the node so it can be given a parent later
+    fn node_marker(
+        &mut self,
+        kind: SyntaxKind,
+        f: impl FnOnce(&mut Self) -> Result<(), ParserError>,
+    ) -> Result<CompletedMarker, ParserError> {
+        let marker = self.start();
+        let result = f(self);
+        let completed = marker.complete(self, kind);
+        result.map(|()| completed)
     }
 
-    // Add these helper methods
-    fn skip_until_sync_point(&mut self) {
-        while let Some(kind) = self.peek_kind() {
-            if self.is_sync_point(kind) {
-                // Found a sync point, consume it and break
-                self.bump().ok();
-                break;
-            }
-            // Skip the current token
-            self.bump().ok();
+    fn current_span(&self) -> Span {
+        let pos = self.next_non_trivia();
+        if pos < self.tokens.len() {
+            Span::new(self.file, self.tokens[pos].range)
+        } else {
+            // Create an empty EOF span just after the last token
+            let end = self
+                .tokens
+                .last()
+                .map(|token| token.range.end())
+                .unwrap_or_default();
+            Span::new(self.file, TextRange::empty(end))
         }
     }
 
-    fn is_sync_point(&self, kind: SyntaxKind) -> bool {
+    /// An empty span just after the last token consumed, where a missing
+    /// token would go
+    fn previous_end_span(&self) -> Span {
+        let end = self.tokens[..self.cursor]
+            .iter()
+            .rev()
+            .find(|token| !token.kind.is_trivia())
+            .map(|token| token.range.end())
+            .unwrap_or_default();
+        Span::new(self.file, TextRange::empty(end))
+    }
+
+    /// At a keyword that can only start a module member. Module variables
+    /// may also start with a type, but so do statements.
+    fn at_declaration_start(&self) -> bool {
         matches!(
-            kind,
-            SyntaxKind::SEMICOLON |
-            SyntaxKind::NEWLINE |
-            SyntaxKind::R_BRACE |
-            // Statement start tokens
-            SyntaxKind::IMPORT_KW |
-            SyntaxKind::PUBLIC_KW |
-            SyntaxKind::FUNCTION_KW |
-            SyntaxKind::IF_KW |
-            SyntaxKind::WHILE_KW |
-            SyntaxKind::RETURN_KW
+            self.peek_kind(),
+            Some(
+                SyntaxKind::IMPORT_KW
+                    | SyntaxKind::PUBLIC_KW
+                    | SyntaxKind::FUNCTION_KW
+                    | SyntaxKind::CONST_KW
+                    | SyntaxKind::TYPE_KW
+                    | SyntaxKind::FINAL_KW
+                    | SyntaxKind::CONFIGURABLE_KW
+            )
         )
     }
 
-    fn synchronize(&mut self) {
-        // Skip until we find a synchronization point
-        self.skip_until_sync_point();
-
-        // Skip any additional newlines/semicolons to get to the next statement
-        while let Some(kind) = self.peek_kind() {
-            if matches!(kind, SyntaxKind::SEMICOLON | SyntaxKind::NEWLINE) {
-                self.bump().ok();
-            } else {
-                break;
-            }
-        }
+    /// Like `at_declaration_start`, but inside a block, where `final`
+    /// starts a local variable declaration instead
+    fn at_declaration_start_in_block(&self) -> bool {
+        self.at_declaration_start() && !self.at(SyntaxKind::FINAL_KW)
     }
 }
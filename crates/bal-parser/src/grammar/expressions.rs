@@ -0,0 +1,358 @@
+//! Expressions, parsed by precedence climbing
+//!
+//! Binary operators are grouped into tiers in `INFIX_TIERS`, loosest first.
+//! Supporting a new operator means adding it to a tier, or adding a tier in
+//! the right place; the parsing loop itself does not change.
+
+use super::super::Parser;
+use crate::event::CompletedMarker;
+use bal_syntax::error::{ParserError, Span};
+use bal_syntax::SyntaxKind;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Assoc {
+    /// `a - b - c` is `(a - b) - c`
+    Left,
+    /// `a < b < c` is an error
+    None,
+}
+
+/// Binary operators that bind equally tightly, and the node they build
+struct Tier {
+    ops: &'static [SyntaxKind],
+    node: SyntaxKind,
+    assoc: Assoc,
+}
+
+const INFIX_TIERS: &[Tier] = &[
+    Tier {
+        ops: &[SyntaxKind::PIPE_PIPE],
+        node: SyntaxKind::LOGICAL_EXPR,
+        assoc: Assoc::Left,
+    },
+    Tier {
+        ops: &[SyntaxKind::AMP_AMP],
+        node: SyntaxKind::LOGICAL_EXPR,
+        assoc: Assoc::Left,
+    },
+    Tier {
+        ops: &[SyntaxKind::PIPE],
+        node: SyntaxKind::BITWISE_EXPR,
+        assoc: Assoc::Left,
+    },
+    Tier {
+        ops: &[SyntaxKind::CARET],
+        node: SyntaxKind::BITWISE_EXPR,
+        assoc: Assoc::Left,
+    },
+    Tier {
+        ops: &[SyntaxKind::AMP],
+        node: SyntaxKind::BITWISE_EXPR,
+        assoc: Assoc::Left,
+    },
+    Tier {
+        ops: &[
+            SyntaxKind::EQ,
+            SyntaxKind::NOT_EQ,
+            SyntaxKind::EXACT_EQ,
+            SyntaxKind::EXACT_NOT_EQ,
+        ],
+        node: SyntaxKind::EQUALITY_EXPR,
+        assoc: Assoc::Left,
+    },
+    Tier {
+        ops: &[
+            SyntaxKind::LT,
+            SyntaxKind::LT_EQ,
+            SyntaxKind::GT,
+            SyntaxKind::GT_EQ,
+        ],
+        node: SyntaxKind::RELATIONAL_EXPR,
+        assoc: Assoc::None,
+    },
+    Tier {
+        ops: &[SyntaxKind::LT_LT, SyntaxKind::GT_GT, SyntaxKind::GT_GT_GT],
+        node: SyntaxKind::SHIFT_EXPR,
+        assoc: Assoc::Left,
+    },
+    Tier {
+        ops: &[SyntaxKind::PLUS, SyntaxKind::MINUS],
+        node: SyntaxKind::ADDITIVE_EXPR,
+        assoc: Assoc::Left,
+    },
+    Tier {
+        ops: &[SyntaxKind::STAR, SyntaxKind::SLASH, SyntaxKind::PERCENT],
+        node: SyntaxKind::MULTIPLICATIVE_EXPR,
+        assoc: Assoc::Left,
+    },
+];
+
+/// Prefix operators bind tighter than every binary operator
+const PREFIX_OPS: &[SyntaxKind] = &[SyntaxKind::MINUS, SyntaxKind::BANG, SyntaxKind::TILDE];
+
+/// The precedence of a binary operator, counting from 1 for the loosest
+/// tier, along with its tier
+fn infix(kind: SyntaxKind) -> Option<(usize, &'static Tier)> {
+    INFIX_TIERS
+        .iter()
+        .enumerate()
+        .find(|(_, tier)| tier.ops.contains(&kind))
+        .map(|(i, tier)| (i + 1, tier))
+}
+
+impl Parser<'_> {
+    pub(crate) fn parse_expr(&mut self) -> Result<(), ParserError> {
+        self.parse_conditional_expr().map(|_| ())
+    }
+
+    /// The condition of an `if` or `while`. `x = 1` there is taken to mean
+    /// `x == 1`: it is reported with a fix and parsed as an equality, so the
+    /// block after it still parses.
+    pub(super) fn parse_condition(&mut self) -> Result<(), ParserError> {
+        let condition = self.parse_conditional_expr()?;
+        if !self.at(SyntaxKind::ASSIGN) {
+            return Ok(());
+        }
+        self.diagnostics.push(ParserError::AssignmentInCondition {
+            span: self.current_span(),
+        });
+        let marker = condition.precede(self);
+        let result = self.bump().and_then(|()| self.parse_expr_bp(1));
+        marker.complete(self, SyntaxKind::EQUALITY_EXPR);
+        result.map(|_| ())
+    }
+
+    /// `cond ? a : b`, looser than every binary operator. Each branch may
+    /// be another conditional, so `a ? b : c ? d : e` nests to the right.
+    fn parse_conditional_expr(&mut self) -> Result<CompletedMarker, ParserError> {
+        let condition = self.parse_expr_bp(1)?;
+        if !self.at(SyntaxKind::QUESTION) {
+            return Ok(condition);
+        }
+        let marker = condition.precede(self);
+        let result = self.parse_conditional_branches();
+        let conditional = marker.complete(self, SyntaxKind::CONDITIONAL_EXPR);
+        result.map(|()| conditional)
+    }
+
+    fn parse_conditional_branches(&mut self) -> Result<(), ParserError> {
+        self.expect(SyntaxKind::QUESTION)?;
+        self.with_then_branch(true, |p| p.parse_conditional_expr().map(|_| ()))?;
+        self.expect(SyntaxKind::COLON)?;
+        self.parse_conditional_expr().map(|_| ())
+    }
+
+    /// Parse with `f` inside (or, for brackets, outside) a then-branch
+    fn with_then_branch(
+        &mut self,
+        in_then_branch: bool,
+        f: impl FnOnce(&mut Self) -> Result<(), ParserError>,
+    ) -> Result<(), ParserError> {
+        let outer = std::mem::replace(&mut self.in_then_branch, in_then_branch);
+        let result = f(self);
+        self.in_then_branch = outer;
+        result
+    }
+
+    /// Whether the adjacent `a:b` next is a qualified name. In a then-branch
+    /// it is only one if another `:` still follows to end the branch, so
+    /// `c ? r:s` is `c ? r : s`.
+    fn at_qualified_name(&self) -> bool {
+        let name = [
+            SyntaxKind::IDENTIFIER,
+            SyntaxKind::COLON,
+            SyntaxKind::IDENTIFIER,
+        ];
+        if !self.at_adjacent(&name) {
+            return false;
+        }
+        if !self.in_then_branch {
+            return true;
+        }
+        // Conditionals nested in the branch need a `:` each as well
+        let mut depth = 0usize;
+        let mut colons_needed = 0usize;
+        let rest = self.tokens[self.next_non_trivia()..]
+            .iter()
+            .map(|token| token.kind)
+            .filter(|kind| !kind.is_trivia())
+            .skip(name.len());
+        for kind in rest {
+            match kind {
+                SyntaxKind::L_PAREN | SyntaxKind::L_BRACKET => depth += 1,
+                SyntaxKind::R_PAREN | SyntaxKind::R_BRACKET if depth == 0 => return false,
+                SyntaxKind::R_PAREN | SyntaxKind::R_BRACKET => depth -= 1,
+                SyntaxKind::QUESTION if depth == 0 => colons_needed += 1,
+                SyntaxKind::COLON if depth == 0 && colons_needed == 0 => return true,
+                SyntaxKind::COLON if depth == 0 => colons_needed -= 1,
+                SyntaxKind::SEMICOLON
+                | SyntaxKind::COMMA
+                | SyntaxKind::L_BRACE
+                | SyntaxKind::R_BRACE
+                    if depth == 0 =>
+                {
+                    return false
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// The binary operator next, and how many tokens spell it. `>>` and
+    /// `>>>` are adjacent `>` tokens, since `>` also closes `map<int>`.
+    fn peek_binary_op(&self) -> Option<(SyntaxKind, usize)> {
+        let gt = SyntaxKind::GT;
+        if self.at_adjacent(&[gt, gt, gt]) {
+            Some((SyntaxKind::GT_GT_GT, 3))
+        } else if self.at_adjacent(&[gt, gt]) {
+            Some((SyntaxKind::GT_GT, 2))
+        } else {
+            self.peek_kind().map(|kind| (kind, 1))
+        }
+    }
+
+    /// Parse an expression whose binary operators all have a precedence of
+    /// at least `min_prec`
+    fn parse_expr_bp(&mut self, min_prec: usize) -> Result<CompletedMarker, ParserError> {
+        let mut lhs = self.parse_prefix_expr()?;
+
+        // The last non-associative operator at this level, if any
+        let mut previous = None;
+        while let Some((op, parts)) = self.peek_binary_op() {
+            let Some((prec, tier)) = infix(op) else {
+                break;
+            };
+            if prec < min_prec {
+                break;
+            }
+            let span = self.current_span();
+            if tier.assoc == Assoc::None {
+                if let Some((previous_prec, first)) = previous {
+                    if previous_prec == prec {
+                        // Keep going as if left associative, so the rest of
+                        // the expression is still parsed
+                        self.diagnostics
+                            .push(ParserError::ChainedComparison { first, span });
+                    }
+                }
+                previous = Some((prec, span));
+            }
+
+            let marker = lhs.precede(self);
+            if parts == 1 {
+                self.bump()?;
+            } else {
+                self.bump_glued(op, parts);
+            }
+            let rhs = self.parse_expr_bp(prec + 1);
+            lhs = marker.complete(self, tier.node);
+            rhs?;
+        }
+        Ok(lhs)
+    }
+
+    fn parse_prefix_expr(&mut self) -> Result<CompletedMarker, ParserError> {
+        match self.peek_kind() {
+            Some(kind) if PREFIX_OPS.contains(&kind) => {
+                self.node_marker(SyntaxKind::UNARY_EXPR, |p| {
+                    p.bump()?;
+                    p.parse_prefix_expr().map(|_| ())
+                })
+            }
+            _ => self.parse_primary_expr(),
+        }
+    }
+
+    fn parse_primary_expr(&mut self) -> Result<CompletedMarker, ParserError> {
+        match self.peek_kind() {
+            // A qualified name is written without spaces, which tells
+            // `io:println(x)` and `m:X` apart from `c ? x : y`
+            Some(SyntaxKind::IDENTIFIER) if self.nth(1) == Some(SyntaxKind::L_PAREN) => {
+                self.parse_function_call()
+            }
+            Some(SyntaxKind::IDENTIFIER) if self.at_qualified_name() => {
+                if self.nth(3) == Some(SyntaxKind::L_PAREN) {
+                    self.parse_function_call()
+                } else {
+                    self.node_marker(SyntaxKind::PRIMARY_EXPR, |p| {
+                        p.node(SyntaxKind::QUALIFIED_IDENTIFIER, |p| {
+                            p.bump()?;
+                            p.bump()?;
+                            p.bump()
+                        })
+                    })
+                }
+            }
+            Some(
+                SyntaxKind::IDENTIFIER
+                | SyntaxKind::INTEGER_LITERAL
+                | SyntaxKind::FLOATING_POINT_LITERAL
+                | SyntaxKind::STRING_LITERAL
+                | SyntaxKind::TRUE_KW
+                | SyntaxKind::FALSE_KW,
+            ) => self.node_marker(SyntaxKind::PRIMARY_EXPR, |p| p.bump()),
+            // The nil literal `()`
+            Some(SyntaxKind::L_PAREN) if self.nth(1) == Some(SyntaxKind::R_PAREN) => self
+                .node_marker(SyntaxKind::PRIMARY_EXPR, |p| {
+                    p.bump()?;
+                    p.bump()
+                }),
+            Some(SyntaxKind::L_PAREN) => self.node_marker(SyntaxKind::PRIMARY_EXPR, |p| {
+                p.bump()?;
+                p.with_then_branch(false, |p| p.parse_expr())?;
+                p.expect(SyntaxKind::R_PAREN)
+            }),
+            _ => Err(self.unexpected("expression")),
+        }
+    }
+
+    /// `f(args)` or `module:f(args)`
+    pub(super) fn parse_function_call(&mut self) -> Result<CompletedMarker, ParserError> {
+        self.node_marker(SyntaxKind::FUNCTION_CALL_EXPR, |p| {
+            if p.nth(1) == Some(SyntaxKind::COLON) {
+                p.node(SyntaxKind::QUALIFIED_IDENTIFIER, |p| {
+                    p.expect(SyntaxKind::IDENTIFIER)?;
+                    p.expect(SyntaxKind::COLON)?;
+                    p.expect(SyntaxKind::IDENTIFIER)
+                })?;
+            } else {
+                p.expect(SyntaxKind::IDENTIFIER)?;
+            }
+            p.node(SyntaxKind::ARG_LIST, |p| {
+                p.expect(SyntaxKind::L_PAREN)?;
+                if !p.at(SyntaxKind::R_PAREN) {
+                    p.with_then_branch(false, |p| {
+                        let mut named = None;
+                        p.parse_arg(&mut named)?;
+                        while p.at(SyntaxKind::COMMA) {
+                            p.bump()?;
+                            p.parse_arg(&mut named)?;
+                        }
+                        Ok(())
+                    })?;
+                }
+                p.expect(SyntaxKind::R_PAREN)
+            })
+        })
+    }
+
+    /// A positional argument, or a named one `x = expr`. `named` is the
+    /// first named argument so far, which no positional one may follow.
+    fn parse_arg(&mut self, named: &mut Option<Span>) -> Result<(), ParserError> {
+        let span = self.current_span();
+        if self.at(SyntaxKind::IDENTIFIER) && self.nth(1) == Some(SyntaxKind::ASSIGN) {
+            named.get_or_insert(span);
+            return self.node(SyntaxKind::NAMED_ARG, |p| {
+                p.bump()?;
+                p.bump()?;
+                p.parse_expr()
+            });
+        }
+        if let Some(named) = *named {
+            self.diagnostics
+                .push(ParserError::PositionalAfterNamed { named, span });
+        }
+        self.parse_expr()
+    }
+}
@@ -0,0 +1,270 @@
+//! Type descriptor grammar
+//!
+//! `T|U` unions bind loosest. The postfix `T?` and `T[n]` bind tightest and
+//! apply left to right, so `int?[]` is an array of optional ints. Every form
+//! has its own node kind; a basic type name such as `int` is a `TYPE_DESC`.
+
+use super::super::Parser;
+use crate::event::CompletedMarker;
+use bal_syntax::error::ParserError;
+use bal_syntax::SyntaxKind;
+
+/// Keywords that are a type descriptor on their own
+pub(super) const BASIC_TYPE_NAMES: &[SyntaxKind] = &[
+    SyntaxKind::INT_KW,
+    SyntaxKind::BOOLEAN_KW,
+    SyntaxKind::FLOAT_KW,
+    SyntaxKind::DECIMAL_KW,
+    SyntaxKind::STRING_KW,
+    SyntaxKind::ANY_KW,
+    SyntaxKind::ANYDATA_KW,
+    SyntaxKind::JSON_KW,
+    SyntaxKind::NEVER_KW,
+    SyntaxKind::READONLY_KW,
+];
+
+impl Parser<'_> {
+    pub(crate) fn parse_type_desc(&mut self) -> Result<(), ParserError> {
+        self.parse_union_type().map(|_| ())
+    }
+
+    /// Whether the next tokens can only start a type descriptor, as opposed
+    /// to an expression or statement
+    pub(super) fn at_type_desc_start(&self) -> bool {
+        match self.peek_kind() {
+            Some(kind) if BASIC_TYPE_NAMES.contains(&kind) => true,
+            Some(SyntaxKind::MAP_KW | SyntaxKind::RECORD_KW | SyntaxKind::L_BRACKET) => true,
+            _ => false,
+        }
+    }
+
+    fn parse_union_type(&mut self) -> Result<CompletedMarker, ParserError> {
+        let first = self.parse_postfix_type()?;
+        if !self.at(SyntaxKind::PIPE) {
+            return Ok(first);
+        }
+        let marker = first.precede(self);
+        let result = self.parse_union_members();
+        let union = marker.complete(self, SyntaxKind::UNION_TYPE_DESC);
+        result.map(|()| union)
+    }
+
+    fn parse_union_members(&mut self) -> Result<(), ParserError> {
+        while self.at(SyntaxKind::PIPE) {
+            self.bump()?;
+            self.parse_postfix_type()?;
+        }
+        Ok(())
+    }
+
+    fn parse_postfix_type(&mut self) -> Result<CompletedMarker, ParserError> {
+        let mut ty = self.parse_primary_type()?;
+        loop {
+            let kind = match self.peek_kind() {
+                Some(SyntaxKind::QUESTION) => SyntaxKind::OPTIONAL_TYPE_DESC,
+                Some(SyntaxKind::L_BRACKET) => SyntaxKind::ARRAY_TYPE_DESC,
+                _ => return Ok(ty),
+            };
+            let marker = ty.precede(self);
+            let result = if kind == SyntaxKind::OPTIONAL_TYPE_DESC {
+                self.bump()
+            } else {
+                self.parse_array_length()
+            };
+            ty = marker.complete(self, kind);
+            result?;
+        }
+    }
+
+    /// `[]`, `[n]`, `[N]` for a constant `N`, or `[*]` to take the length
+    /// from the initializer
+    fn parse_array_length(&mut self) -> Result<(), ParserError> {
+        self.expect(SyntaxKind::L_BRACKET)?;
+        if matches!(
+            self.peek_kind(),
+            Some(SyntaxKind::INTEGER_LITERAL | SyntaxKind::IDENTIFIER | SyntaxKind::STAR)
+        ) {
+            self.bump()?;
+        }
+        self.expect(SyntaxKind::R_BRACKET)
+    }
+
+    fn parse_primary_type(&mut self) -> Result<CompletedMarker, ParserError> {
+        match self.peek_kind() {
+            Some(kind) if BASIC_TYPE_NAMES.contains(&kind) => {
+                self.node_marker(SyntaxKind::TYPE_DESC, |p| p.bump())
+            }
+            Some(SyntaxKind::L_PAREN) if self.nth(1) == Some(SyntaxKind::R_PAREN) => self
+                .node_marker(SyntaxKind::NIL_TYPE_DESC, |p| {
+                    p.bump()?;
+                    p.bump()
+                }),
+            Some(SyntaxKind::L_PAREN) => {
+                self.node_marker(SyntaxKind::PARENTHESIZED_TYPE_DESC, |p| {
+                    p.bump()?;
+                    p.parse_type_desc()?;
+                    p.expect(SyntaxKind::R_PAREN)
+                })
+            }
+            Some(SyntaxKind::L_BRACKET) => self.parse_tuple_type(),
+            Some(SyntaxKind::MAP_KW) => self.node_marker(SyntaxKind::MAP_TYPE_DESC, |p| {
+                p.bump()?;
+                p.expect(SyntaxKind::LT)?;
+                p.parse_type_desc()?;
+                p.expect(SyntaxKind::GT)
+            }),
+            Some(SyntaxKind::RECORD_KW) => self.parse_record_type(),
+            Some(
+                SyntaxKind::INTEGER_LITERAL
+                | SyntaxKind::FLOATING_POINT_LITERAL
+                | SyntaxKind::STRING_LITERAL
+                | SyntaxKind::TRUE_KW
+                | SyntaxKind::FALSE_KW,
+            ) => self.node_marker(SyntaxKind::SINGLETON_TYPE_DESC, |p| p.bump()),
+            Some(SyntaxKind::MINUS)
+                if matches!(
+                    self.nth(1),
+                    Some(SyntaxKind::INTEGER_LITERAL | SyntaxKind::FLOATING_POINT_LITERAL)
+                ) =>
+            {
+                self.node_marker(SyntaxKind::SINGLETON_TYPE_DESC, |p| {
+                    p.bump()?;
+                    p.bump()
+                })
+            }
+            Some(SyntaxKind::IDENTIFIER) => self.node_marker(SyntaxKind::TYPE_REFERENCE, |p| {
+                if p.nth(1) == Some(SyntaxKind::COLON) {
+                    p.node(SyntaxKind::QUALIFIED_IDENTIFIER, |p| {
+                        p.bump()?;
+                        p.bump()?;
+                        p.expect(SyntaxKind::IDENTIFIER)
+                    })
+                } else {
+                    p.bump()
+                }
+            }),
+            _ => Err(self.unexpected("type descriptor")),
+        }
+    }
+
+    /// `[T, U]`, where the last member may be a rest descriptor `V...`
+    fn parse_tuple_type(&mut self) -> Result<CompletedMarker, ParserError> {
+        self.node_marker(SyntaxKind::TUPLE_TYPE_DESC, |p| {
+            p.expect(SyntaxKind::L_BRACKET)?;
+            if !p.at(SyntaxKind::R_BRACKET) {
+                loop {
+                    let member = p.parse_union_type()?;
+                    if p.at(SyntaxKind::ELLIPSIS) {
+                        let marker = member.precede(p);
+                        p.bump()?;
+                        marker.complete(p, SyntaxKind::TUPLE_REST_DESC);
+                        break;
+                    }
+                    if !p.at(SyntaxKind::COMMA) {
+                        break;
+                    }
+                    p.bump()?;
+                }
+            }
+            p.expect(SyntaxKind::R_BRACKET)
+        })
+    }
+
+    /// `record { ... }`, which is open to other fields, or `record {| ... |}`,
+    /// which is closed unless it ends with a rest descriptor `T...;`
+    fn parse_record_type(&mut self) -> Result<CompletedMarker, ParserError> {
+        self.node_marker(SyntaxKind::RECORD_TYPE_DESC, |p| {
+            p.expect(SyntaxKind::RECORD_KW)?;
+            let closed = p.at(SyntaxKind::L_BRACE_PIPE);
+            let close = if closed {
+                SyntaxKind::R_BRACE_PIPE
+            } else {
+                SyntaxKind::R_BRACE
+            };
+            p.expect_one_of(&[SyntaxKind::L_BRACE, SyntaxKind::L_BRACE_PIPE])?;
+
+            while !p.at(close) && !p.at_end() {
+                let start = p.cursor;
+                if let Err(e) = p.parse_record_member(closed) {
+                    p.report(e);
+                    p.recover_until(&[
+                        SyntaxKind::SEMICOLON,
+                        SyntaxKind::R_BRACE,
+                        SyntaxKind::R_BRACE_PIPE,
+                    ]);
+                    if p.at(SyntaxKind::SEMICOLON) {
+                        p.bump()?;
+                    } else if p.cursor == start {
+                        break;
+                    }
+                }
+            }
+            p.expect(close)
+        })
+    }
+
+    fn parse_record_member(&mut self, closed: bool) -> Result<(), ParserError> {
+        if self.at(SyntaxKind::STAR) {
+            return self.node(SyntaxKind::TYPE_INCLUSION, |p| {
+                p.bump()?;
+                p.parse_type_desc()?;
+                p.expect_semicolon("type inclusion")
+            });
+        }
+
+        // Whether this is a field or a rest descriptor is only known once
+        // the type has been parsed
+        let marker = self.start();
+        let mut kind = SyntaxKind::RECORD_FIELD;
+        let result = self.parse_record_field(closed, &mut kind);
+        marker.complete(self, kind);
+        result
+    }
+
+    /// `[readonly] T name [? | = default];`, or `T...;` in a closed record
+    fn parse_record_field(
+        &mut self,
+        closed: bool,
+        kind: &mut SyntaxKind,
+    ) -> Result<(), ParserError> {
+        if self.at_readonly_qualifier() {
+            self.bump()?;
+        }
+        self.parse_type_desc()?;
+        if closed && self.at(SyntaxKind::ELLIPSIS) {
+            *kind = SyntaxKind::RECORD_REST_DESC;
+            self.bump()?;
+        } else {
+            self.expect(SyntaxKind::IDENTIFIER)?;
+            if self.at(SyntaxKind::QUESTION) {
+                self.bump()?;
+            } else if self.at(SyntaxKind::ASSIGN) {
+                self.bump()?;
+                self.parse_expr()?;
+            }
+        }
+        self.expect_semicolon("record field")
+    }
+
+    /// `readonly` qualifies a field when a type follows it, and is the
+    /// field's type when the name does, as in `readonly x;`
+    fn at_readonly_qualifier(&self) -> bool {
+        self.at(SyntaxKind::READONLY_KW)
+            && match self.nth(1) {
+                Some(SyntaxKind::IDENTIFIER) => {
+                    matches!(
+                        self.nth(2),
+                        Some(SyntaxKind::IDENTIFIER | SyntaxKind::COLON)
+                    )
+                }
+                Some(
+                    SyntaxKind::PIPE
+                    | SyntaxKind::QUESTION
+                    | SyntaxKind::L_BRACKET
+                    | SyntaxKind::ELLIPSIS,
+                ) => false,
+                Some(_) => true,
+                None => false,
+            }
+    }
+}
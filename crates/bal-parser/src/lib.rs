@@ -1,38 +1,92 @@
 //! Ballerina Parser Library
 //! Handles incremental parsing and syntax tree construction
 
-use bal_syntax::error::{ParserError, Span};
-use bal_syntax::SyntaxKind;
-use rowan::{GreenNode, GreenNodeBuilder};
+use bal_syntax::diagnostic::{Diagnostic, Diagnostics};
+use bal_syntax::error::ParserError;
+use bal_syntax::source::FileId;
+use bal_syntax::token::Token;
+use event::Event;
+use rowan::{GreenNode, NodeCache};
+use sink::Sink;
+
+pub use fragment::{parse_expression, parse_module_member, parse_statement, parse_type_desc};
 
 pub type Result<T> = std::result::Result<T, ParserError>;
 
-pub struct Parser {
-    builder: GreenNodeBuilder<'static>,
-    tokens: Vec<(SyntaxKind, String, Span)>,
+/// Parses a token stream into events, which a `Sink` then turns into a
+/// syntax tree. Tokens only hold ranges into `source`, so no token text is
+/// copied until it reaches the tree.
+pub struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
     cursor: usize,
-    file: Option<String>,
+    file: FileId,
+    events: Vec<Event>,
+    cache: Option<&'a mut NodeCache>,
+    /// Errors the parser recovered from
+    diagnostics: Diagnostics,
+    /// Inside the then-branch of a conditional, where a `:` may end the
+    /// branch rather than qualify a name
+    in_then_branch: bool,
 }
 
-impl Parser {
-    pub fn new(file: Option<String>, tokens: Vec<(SyntaxKind, String, Span)>) -> Self {
+impl<'a> Parser<'a> {
+    pub fn new(file: FileId, source: &'a str, tokens: Vec<Token>) -> Self {
         Self {
-            builder: GreenNodeBuilder::new(),
+            source,
             tokens,
             cursor: 0,
             file,
+            events: Vec::new(),
+            cache: None,
+            diagnostics: Diagnostics::new(),
+            in_then_branch: false,
+        }
+    }
+
+    /// Parse using a node cache shared with other files, so identical
+    /// nodes and tokens across the project are only allocated once
+    pub fn with_cache(
+        file: FileId,
+        source: &'a str,
+        tokens: Vec<Token>,
+        cache: &'a mut NodeCache,
+    ) -> Self {
+        Self {
+            cache: Some(cache),
+            ..Self::new(file, source, tokens)
         }
     }
 
-    pub fn parse(mut self) -> Result<GreenNode> {
-        self.parse_module_part()?;
-        Ok(self.builder.finish())
+    /// Build the tree, along with diagnostics for every syntax error.
+    /// Parsing never gives up: regions that cannot be parsed are kept in
+    /// `ERROR` nodes, so the tree always covers the whole input.
+    pub fn parse(mut self) -> (GreenNode, Vec<Diagnostic>) {
+        // Every declaration recovers from its own errors
+        let _ = self.parse_module_part();
+        (self.build_tree(), self.diagnostics.into_vec())
+    }
+
+    /// Replay the events recorded so far into a tree
+    pub(crate) fn build_tree(&mut self) -> GreenNode {
+        let events = std::mem::take(&mut self.events);
+        let sink = match self.cache.take() {
+            Some(cache) => Sink::with_cache(self.source, &self.tokens, cache),
+            None => Sink::new(self.source, &self.tokens),
+        };
+        sink.finish(events)
     }
 }
 
 pub mod event;
+pub mod fragment;
 pub mod grammar;
+pub mod reparse;
 pub mod sink;
 
+#[cfg(test)]
+mod fragment_test;
 #[cfg(test)]
 mod parser_test;
+#[cfg(test)]
+mod reparse_test;
@@ -1,77 +1,35 @@
 #[cfg(test)]
 mod tests {
     use crate::Parser;
-    use bal_syntax::error::Span;
-    use bal_syntax::SyntaxKind;
+    use bal_syntax::diagnostic::{codes, Diagnostic};
+    use bal_syntax::source::FileId;
+    use bal_syntax::token::Token;
+    use bal_syntax::{BallerinaLanguage, SyntaxKind};
+    use rowan::SyntaxNode;
 
+    /// The tree followed by the parser's diagnostics
     fn parse(input: &str) -> String {
         let tokens = tokenize(input);
-        let parser = Parser::new(Some("test.bal".to_string()), tokens);
-        let node = parser.parse();
-        format!("{:#?}", node)
-    }
-
-    fn tokenize(input: &str) -> Vec<(SyntaxKind, String, Span)> {
-        use bal_syntax::lexer::{Lexer, Token};
-
-        let mut lexer = Lexer::new(input);
-        let mut tokens = Vec::new();
-
-        while let Some(result) = lexer.next_token() {
-            if let Ok(token_info) = result {
-                // Skip whitespace and newlines
-                match token_info.kind {
-                    Token::Import => {
-                        tokens.push((SyntaxKind::IMPORT_KW, token_info.text, token_info.span))
-                    }
-                    Token::Public => {
-                        tokens.push((SyntaxKind::PUBLIC_KW, token_info.text, token_info.span))
-                    }
-                    Token::Function => {
-                        tokens.push((SyntaxKind::FUNCTION_KW, token_info.text, token_info.span))
-                    }
-                    Token::Returns => {
-                        tokens.push((SyntaxKind::RETURNS_KW, token_info.text, token_info.span))
-                    }
-                    Token::Int => {
-                        tokens.push((SyntaxKind::INT_KW, token_info.text, token_info.span))
-                    }
-                    Token::Boolean => {
-                        tokens.push((SyntaxKind::BOOLEAN_KW, token_info.text, token_info.span))
-                    }
-                    Token::Identifier => {
-                        tokens.push((SyntaxKind::IDENTIFIER, token_info.text, token_info.span))
-                    }
-                    Token::LParen => {
-                        tokens.push((SyntaxKind::L_PAREN, token_info.text, token_info.span))
-                    }
-                    Token::RParen => {
-                        tokens.push((SyntaxKind::R_PAREN, token_info.text, token_info.span))
-                    }
-                    Token::LBrace => {
-                        tokens.push((SyntaxKind::L_BRACE, token_info.text, token_info.span))
-                    }
-                    Token::RBrace => {
-                        tokens.push((SyntaxKind::R_BRACE, token_info.text, token_info.span))
-                    }
-                    Token::Comma => {
-                        tokens.push((SyntaxKind::COMMA, token_info.text, token_info.span))
-                    }
-                    Token::Semicolon => {
-                        tokens.push((SyntaxKind::SEMICOLON, token_info.text, token_info.span))
-                    }
-                    Token::Slash => {
-                        tokens.push((SyntaxKind::SLASH, token_info.text, token_info.span))
-                    }
-                    Token::LineComment => {
-                        tokens.push((SyntaxKind::COMMENT, token_info.text, token_info.span))
-                    }
-                    Token::Newline => continue, // Skip newlines
-                    _ => continue,              // Skip other tokens
-                }
-            }
+        let parser = Parser::new(FileId::default(), input, tokens);
+        let (green, diagnostics) = parser.parse();
+        let mut out = format!("{:#?}", SyntaxNode::<BallerinaLanguage>::new_root(green));
+        for diagnostic in diagnostics {
+            out.push_str(&format!("{}\n", diagnostic));
         }
-        tokens
+        out
+    }
+
+    fn tokenize(input: &str) -> Vec<Token> {
+        bal_syntax::lexer::lex(FileId::default(), input).tokens
+    }
+
+    fn round_trip(input: &str) -> String {
+        let tokens = tokenize(input);
+        let parser = Parser::new(FileId::default(), input, tokens);
+        let (green, _) = parser.parse();
+        SyntaxNode::<BallerinaLanguage>::new_root(green)
+            .text()
+            .to_string()
     }
 
     #[test]
@@ -118,6 +76,26 @@ mod tests {
         insta::assert_snapshot!(parse(input));
     }
 
+    #[test]
+    fn test_import_forms() {
+        let input = "import ballerina/lang.'int as ints;\nimport myorg/pkg.sub;\nimport proj.util as _;\nimport foo/bar as ;\n";
+        insta::assert_snapshot!(parse(input));
+    }
+
+    #[test]
+    fn test_module_level_declarations() {
+        let input =
+            "public const int MAX = 10;\nconst LIMIT = MAX * 2;\nfinal int start = LIMIT;\n\
+                     configurable boolean verbose = ?;\npublic type Count int;\nint counter = 0;\n";
+        insta::assert_snapshot!(parse(input));
+    }
+
+    #[test]
+    fn test_bad_module_variable_does_not_hide_the_next() {
+        let input = "int x = ;\nint y = 2;\npublic 1;\ntype T;\nconst C = 1;\n";
+        insta::assert_snapshot!(parse(input));
+    }
+
     #[test]
     fn test_error_recovery_missing_semicolon() {
         let input = "import foo/bar"; // Missing semicolon
@@ -129,4 +107,275 @@ mod tests {
         let input = "function bad(a, int b) { }"; // Missing type for first param
         insta::assert_snapshot!(parse(input));
     }
+
+    #[test]
+    fn test_bad_declarations_do_not_hide_later_functions() {
+        let input = "import foo/;\n123 +\nfunction f(int a {\n    int x = ;\nfunction g() { }\n";
+        insta::assert_snapshot!(parse(input));
+        assert_eq!(round_trip(input), input);
+    }
+
+    /// Parse the statements in a function body
+    fn parse_body(body: &str) -> String {
+        parse(&format!("function f() {{\n{}\n}}", body))
+    }
+
+    #[test]
+    fn test_local_var_decl_and_assignment() {
+        insta::assert_snapshot!(parse_body("int x = 1;\nboolean done = false;\nx = x + 1;"));
+    }
+
+    #[test]
+    fn test_local_final_var_decl() {
+        insta::assert_snapshot!(parse_body("final int u = 1;\nio:println(u);"));
+    }
+
+    #[test]
+    fn test_function_call_statements() {
+        insta::assert_snapshot!(parse_body("io:println(x);\nreset();\nlog(1, f(2));"));
+    }
+
+    #[test]
+    fn test_return_statements() {
+        insta::assert_snapshot!(parse_body("return;\nreturn -x;"));
+    }
+
+    #[test]
+    fn test_if_else_if_chain() {
+        insta::assert_snapshot!(parse_body(
+            "if x < 0 {\n    return -1;\n} else if x == 0 {\n    return 0;\n} else {\n    return 1;\n}"
+        ));
+    }
+
+    #[test]
+    fn test_while_with_nested_blocks() {
+        insta::assert_snapshot!(parse_body(
+            "while i < n {\n    if i % 2 == 0 {\n        i = i + 1;\n        continue;\n    }\n    if !done {\n        break;\n    }\n}"
+        ));
+    }
+
+    #[test]
+    fn test_type_descriptor_precedence() {
+        insta::assert_snapshot!(parse_body("int|string?[] xs = x;\n(int|())[2] pair = p;"));
+    }
+
+    #[test]
+    fn test_structured_type_descriptors() {
+        let input = "type Pair [int, string...];\ntype Counts map<map<int>>;\n\
+                     type Mode \"on\"|\"off\"|-1;\ntype Any any|anydata|json|never|readonly;\n\
+                     type Name io:Name;\n";
+        insta::assert_snapshot!(parse(input));
+    }
+
+    #[test]
+    fn test_record_type_descriptors() {
+        let input = "type Open record {\n    int id;\n    string name?;\n    readonly int[] tags;\n    int count = 0;\n};\n\
+                     type Closed record {|\n    *Open;\n    readonly x;\n    decimal...;\n|};\n";
+        insta::assert_snapshot!(parse(input));
+    }
+
+    #[test]
+    fn test_bad_record_field_recovers() {
+        let input = "type R record {| int; string name; |};\nconst C = 1;\n";
+        insta::assert_snapshot!(parse(input));
+    }
+
+    #[test]
+    fn test_parameter_kinds() {
+        insta::assert_snapshot!(parse(
+            "function f(int a, *Options opts, int b = a + 1, string... rest) {\n}\n"
+        ));
+    }
+
+    #[test]
+    fn test_included_record_param_after_defaultable() {
+        let input = "function g(int a, int b = 2, *Options opts, int... rest) {\n}\n";
+        let parser = Parser::new(FileId::default(), input, tokenize(input));
+        let (green, diagnostics) = parser.parse();
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        let kinds: Vec<_> = SyntaxNode::<BallerinaLanguage>::new_root(green)
+            .descendants()
+            .map(|node| node.kind())
+            .filter(|kind| {
+                matches!(
+                    kind,
+                    SyntaxKind::PARAM
+                        | SyntaxKind::DEFAULTABLE_PARAM
+                        | SyntaxKind::INCLUDED_RECORD_PARAM
+                        | SyntaxKind::REST_PARAM
+                )
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                SyntaxKind::PARAM,
+                SyntaxKind::DEFAULTABLE_PARAM,
+                SyntaxKind::INCLUDED_RECORD_PARAM,
+                SyntaxKind::REST_PARAM
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parameters_out_of_order() {
+        insta::assert_snapshot!(parse(
+            "function f(int a = 1, int b, int... c, int d = 2) {\n}\n"
+        ));
+    }
+
+    #[test]
+    fn test_named_arguments() {
+        insta::assert_snapshot!(parse_body("connect(host, retries = 3 + 1);\nf(x = 1, 2);"));
+    }
+
+    #[test]
+    fn test_expression_precedence() {
+        insta::assert_snapshot!(parse_body("int x = -a + b * (c - d) % 3 != e - f >= g;"));
+    }
+
+    #[test]
+    fn test_logical_bitwise_and_shift_precedence() {
+        insta::assert_snapshot!(parse_body(
+            "x = a || b && c | d ^ e & f === g < h << i >> j >>> ~k + l;"
+        ));
+    }
+
+    #[test]
+    fn test_conditional_nests_to_the_right() {
+        insta::assert_snapshot!(parse_body("x = a ? b : c ? io:f() : d || e;"));
+    }
+
+    #[test]
+    fn test_qualified_references_and_conditionals() {
+        insta::assert_snapshot!(parse_body(
+            "int a = c ? r:s;\nint b = m:X + 1;\nint d = c ? m:X : io:f(r:s);"
+        ));
+    }
+
+    #[test]
+    fn test_shift_needs_adjacent_angle_brackets() {
+        let output = parse_body("map<map<int>> m = n;\nx = a >>> b >> c;");
+        assert!(
+            !output.contains("error") && !output.contains("unexpected"),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains("GT_GT_GT@") && output.contains("GT_GT@"),
+            "{}",
+            output
+        );
+
+        let output = parse_body("x = a > > b;");
+        assert!(
+            output.contains("expected expression, found '>'"),
+            "{}",
+            output
+        );
+    }
+
+    #[test]
+    fn test_left_associative_operators() {
+        insta::assert_snapshot!(parse_body("x = a - b - c == d != e;"));
+    }
+
+    #[test]
+    fn test_unary_and_qualified_call() {
+        insta::assert_snapshot!(parse_body("io:println(!-x * (1 + f()));"));
+    }
+
+    #[test]
+    fn test_chained_comparison_is_an_error() {
+        insta::assert_snapshot!(parse_body("return a < b < c;"));
+    }
+
+    #[test]
+    fn test_comparisons_across_tiers_are_allowed() {
+        let output = parse_body("return a < b == c >= d;");
+        assert!(!output.contains("error"), "{}", output);
+    }
+
+    #[test]
+    fn test_statement_error_recovery() {
+        insta::assert_snapshot!(parse_body(
+            "int x = ;\nx = 1 2;\nelse { y; }\nif x { return; }\nreturn x"
+        ));
+    }
+
+    /// The parser's diagnostics for a function body, with where each starts
+    fn body_diagnostics(body: &str) -> Vec<(usize, Diagnostic)> {
+        let input = format!("function f() {{\n{}\n}}", body);
+        let parser = Parser::new(FileId::default(), &input, tokenize(&input));
+        let (_, diagnostics) = parser.parse();
+        diagnostics
+            .into_iter()
+            .map(|diagnostic| (diagnostic.span().unwrap().start() - 15, diagnostic))
+            .collect()
+    }
+
+    #[test]
+    fn test_missing_semicolon_at_end_of_line() {
+        let diagnostics = body_diagnostics("int x = 1\nx = 2;\nbreak");
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|(start, diagnostic)| (*start, diagnostic.code))
+            .collect();
+        assert_eq!(
+            found,
+            vec![(9, codes::MISSING_TOKEN), (22, codes::MISSING_TOKEN)]
+        );
+        let edits = &diagnostics[0].1.fixes[0].edits;
+        assert_eq!(edits[0].insert, ";");
+        assert_eq!(usize::from(edits[0].delete.start()), 15 + 9);
+    }
+
+    #[test]
+    fn test_assignment_in_condition_is_parsed_as_equality() {
+        let body = "while x = 2 {\n}\nif x = 2 {\n}";
+        insta::assert_snapshot!(parse_body(body));
+        let diagnostics = body_diagnostics(body);
+        assert_eq!(diagnostics.len(), 2);
+        let (start, diagnostic) = &diagnostics[1];
+        assert_eq!(
+            (*start, diagnostic.code),
+            (21, codes::ASSIGNMENT_IN_CONDITION)
+        );
+        let edit = &diagnostic.fixes[0].edits[0];
+        assert_eq!(edit.insert, "==");
+        assert_eq!(usize::from(edit.delete.len()), 1);
+    }
+
+    #[test]
+    fn test_stray_closing_record_brace_in_statement() {
+        insta::assert_snapshot!(parse_body("x = |} 1;\nreturn;"));
+    }
+
+    #[test]
+    fn test_nil_literal() {
+        insta::assert_snapshot!(parse_body("int? maybe = ();\nf(( ), (x));"));
+    }
+
+    #[test]
+    fn test_comments_attach_to_next_declaration() {
+        let input =
+            "// license\n\n# Docs\nfunction f() {\n    // why\n    int x = 1; // trailing\n}\n";
+        insta::assert_snapshot!(parse(input));
+    }
+
+    #[test]
+    fn test_lossless_round_trip() {
+        let inputs = [
+            "",
+            "   \n\t",
+            "import foo/bar;",
+            "// header\nimport  ballerina/io ;\r\n\npublic function main() {\n}\n",
+            "function add(int a,int b)returns int{ } // trailing\n",
+            "function f() { $x; }\n$ @ stray",
+            "function f() {\n  if a {} else if b { x = 1 } else { while c { break; } }\n  g(;\n}\n",
+        ];
+        for input in inputs {
+            assert_eq!(round_trip(input), input);
+        }
+    }
 }
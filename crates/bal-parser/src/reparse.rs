@@ -0,0 +1,143 @@
+//! Incremental reparsing after a single text edit
+//!
+//! An edit inside an identifier, literal or comment only relexes that token.
+//! An edit inside the braces of a statement block only reparses that block.
+//! Anything else reparses the whole file. Whichever path is taken, the result
+//! is the same tree a full parse of the new text would produce.
+
+use crate::Parser;
+use bal_syntax::lexer::lex;
+use bal_syntax::source::FileId;
+use bal_syntax::text_edit::TextEdit;
+use bal_syntax::token::Token;
+use bal_syntax::{BallerinaLanguage, SyntaxKind};
+use rowan::{GreenNode, GreenToken, Language, NodeOrToken, TextRange, TextSize};
+
+type SyntaxNode = rowan::SyntaxNode<BallerinaLanguage>;
+type SyntaxToken = rowan::SyntaxToken<BallerinaLanguage>;
+
+/// Apply `edit` to `text`, whose syntax tree is `tree`, and return the tree
+/// for the new text. Diagnostics are not updated; get them from a full parse.
+pub fn reparse(file: FileId, tree: &GreenNode, text: &str, edit: &TextEdit) -> GreenNode {
+    let root = SyntaxNode::new_root(tree.clone());
+    if let Some(green) = reparse_token(&root, edit).or_else(|| reparse_block(file, &root, edit)) {
+        return green;
+    }
+
+    let text = edit.apply(text);
+    Parser::new(file, &text, lex(file, &text).tokens).parse().0
+}
+
+/// Relex the single token containing the edit, if the new text still lexes
+/// as one token of the same kind without merging into its neighbours
+pub(crate) fn reparse_token(root: &SyntaxNode, edit: &TextEdit) -> Option<GreenNode> {
+    let token = root.covering_element(edit.delete).into_token()?;
+    if !matches!(
+        token.kind(),
+        SyntaxKind::IDENTIFIER
+            | SyntaxKind::INTEGER_LITERAL
+            | SyntaxKind::FLOATING_POINT_LITERAL
+            | SyntaxKind::STRING_LITERAL
+            | SyntaxKind::COMMENT
+            | SyntaxKind::DOC_COMMENT
+    ) {
+        return None;
+    }
+
+    let start = token.text_range().start();
+    let local = TextEdit::replace(edit.delete - start, edit.insert.clone());
+    let new_text = local.apply(token.text());
+
+    // Lex together with the neighbouring tokens, which could otherwise
+    // combine with the new text (as `0` and `x1` would)
+    let prev = token.prev_token();
+    let next = token.next_token();
+    let context: String = [
+        prev.as_ref().map(SyntaxToken::text),
+        Some(&new_text),
+        next.as_ref().map(SyntaxToken::text),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let expected = [
+        prev.as_ref().map(|t| (t.kind(), TextSize::of(t.text()))),
+        Some((token.kind(), TextSize::of(new_text.as_str()))),
+        next.as_ref().map(|t| (t.kind(), TextSize::of(t.text()))),
+    ];
+    let lexed: Vec<_> = lex(FileId::default(), &context)
+        .tokens
+        .into_iter()
+        .map(|t| (t.kind, t.range.len()))
+        .collect();
+    if !lexed.iter().copied().eq(expected.into_iter().flatten()) {
+        return None;
+    }
+
+    let green = GreenToken::new(BallerinaLanguage::kind_to_raw(token.kind()), &new_text);
+    Some(token.replace_with(green))
+}
+
+/// Reparse the innermost statement block whose braces enclose the edit
+pub(crate) fn reparse_block(file: FileId, root: &SyntaxNode, edit: &TextEdit) -> Option<GreenNode> {
+    let node = match root.covering_element(edit.delete) {
+        NodeOrToken::Node(node) => node,
+        NodeOrToken::Token(token) => token.parent()?,
+    };
+    node.ancestors()
+        .filter(|node| node.kind() == SyntaxKind::STMT_BLOCK)
+        .find_map(|block| reparse_block_node(file, &block, edit))
+}
+
+fn reparse_block_node(file: FileId, block: &SyntaxNode, edit: &TextEdit) -> Option<GreenNode> {
+    let (l_brace, r_brace) = braces(block)?;
+    if edit.delete.start() < l_brace.end() || edit.delete.end() > r_brace.start() {
+        return None;
+    }
+
+    let start = block.text_range().start();
+    let local = TextEdit::replace(edit.delete - start, edit.insert.clone());
+    let text = local.apply(&block.text().to_string());
+    let tokens = lex(file, &text).tokens;
+    if !is_balanced(&tokens) {
+        return None;
+    }
+
+    let mut parser = Parser::new(file, &text, tokens);
+    parser.parse_stmt_block().ok()?;
+    if !parser.at_end() {
+        return None;
+    }
+    Some(block.replace_with(parser.build_tree()))
+}
+
+/// The ranges of the braces that open and close `block`
+fn braces(block: &SyntaxNode) -> Option<(TextRange, TextRange)> {
+    let mut tokens = block
+        .children_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .filter(|token| !token.kind().is_trivia());
+    let l_brace = tokens.next().filter(|t| t.kind() == SyntaxKind::L_BRACE)?;
+    let r_brace = tokens.last().filter(|t| t.kind() == SyntaxKind::R_BRACE)?;
+    Some((l_brace.text_range(), r_brace.text_range()))
+}
+
+/// Whether the tokens open with `{` and only close it at the final token
+fn is_balanced(tokens: &[Token]) -> bool {
+    let mut tokens = tokens.iter().filter(|t| !t.kind.is_trivia());
+    if tokens.next().map(|t| t.kind) != Some(SyntaxKind::L_BRACE) {
+        return false;
+    }
+    let mut depth = 1;
+    for token in tokens {
+        if depth == 0 {
+            return false;
+        }
+        match token.kind {
+            SyntaxKind::L_BRACE => depth += 1,
+            SyntaxKind::R_BRACE => depth -= 1,
+            _ => {}
+        }
+    }
+    depth == 0
+}
@@ -0,0 +1,161 @@
+#[cfg(test)]
+mod tests {
+    use crate::reparse::{reparse, reparse_block, reparse_token};
+    use crate::Parser;
+    use bal_syntax::source::FileId;
+    use bal_syntax::text_edit::TextEdit;
+    use bal_syntax::BallerinaLanguage;
+    use rowan::{GreenNode, TextRange, TextSize};
+
+    type SyntaxNode = rowan::SyntaxNode<BallerinaLanguage>;
+
+    const SOURCE: &str = "import foo/bar;
+
+# Adds one
+function inc(int x) returns int {
+    int y = x + 1; // bump
+    return \"y\";
+}
+
+function zero() returns int { return 0; }
+";
+
+    fn parse(text: &str) -> GreenNode {
+        let tokens = bal_syntax::lexer::lex(FileId::default(), text).tokens;
+        let (green, _) = Parser::new(FileId::default(), text, tokens).parse();
+        green
+    }
+
+    /// An edit replacing the first occurrence of `target` in `text`
+    fn replace(text: &str, target: &str, insert: &str) -> TextEdit {
+        let start = text.find(target).expect("target should be in text");
+        let range = TextRange::at(TextSize::try_from(start).unwrap(), TextSize::of(target));
+        TextEdit::replace(range, insert)
+    }
+
+    /// Reparse incrementally and check the result against a full parse
+    fn check(text: &str, edit: &TextEdit) -> SyntaxNode {
+        let old = parse(text);
+        let incremental = reparse(FileId::default(), &old, text, edit);
+        let new_text = edit.apply(text);
+        let full = parse(&new_text);
+
+        let node = SyntaxNode::new_root(incremental.clone());
+        assert_eq!(node.text().to_string(), new_text);
+        assert_eq!(
+            format!("{:#?}", node),
+            format!("{:#?}", SyntaxNode::new_root(full.clone()))
+        );
+        assert_eq!(incremental, full);
+        node
+    }
+
+    fn root(text: &str) -> SyntaxNode {
+        SyntaxNode::new_root(parse(text))
+    }
+
+    #[test]
+    fn test_relex_identifier() {
+        let edit = replace(SOURCE, "inc", "increment");
+        assert!(reparse_token(&root(SOURCE), &edit).is_some());
+        check(SOURCE, &edit);
+    }
+
+    #[test]
+    fn test_relex_literals_and_comments() {
+        for (target, insert) in [
+            ("1", "42"),
+            ("\"y\"", "\"why\""),
+            ("bump", "increment"),
+            ("Adds", "Increments"),
+        ] {
+            let edit = replace(SOURCE, target, insert);
+            assert!(
+                reparse_token(&root(SOURCE), &edit).is_some(),
+                "'{}' should be relexed in place",
+                target
+            );
+            check(SOURCE, &edit);
+        }
+    }
+
+    #[test]
+    fn test_relex_rejects_kind_change() {
+        // `x` becoming a keyword, a literal that merges with its neighbour,
+        // and a comment that swallows a newline cannot be relexed alone
+        let cases = [
+            ("x + 1", "int + 1"),
+            ("0; }", "0x; }"),
+            ("// bump", "// bump\nint z;"),
+        ];
+        for (target, insert) in cases {
+            let edit = replace(SOURCE, target, insert);
+            assert!(reparse_token(&root(SOURCE), &edit).is_none());
+            check(SOURCE, &edit);
+        }
+    }
+
+    #[test]
+    fn test_relex_checks_neighbours() {
+        // `0x1` would lex as one hex literal, so `0` and `x1` may not both stay
+        let text = "function f() { return 0y; }";
+        let edit = replace(text, "y", "x1");
+        assert!(reparse_token(&root(text), &edit).is_none());
+        check(text, &edit);
+
+        let text = "function f() { return 0xa; }";
+        let edit = replace(text, "xa", "xab");
+        assert!(reparse_token(&root(text), &edit).is_some());
+        check(text, &edit);
+    }
+
+    #[test]
+    fn test_reparse_block() {
+        let edit = replace(SOURCE, "int y = x + 1;", "int y = x + 1;\n    y = y * 2;");
+        assert!(reparse_token(&root(SOURCE), &edit).is_none());
+        assert!(reparse_block(FileId::default(), &root(SOURCE), &edit).is_some());
+        check(SOURCE, &edit);
+    }
+
+    #[test]
+    fn test_reparse_block_rejects_unbalanced_braces() {
+        let edit = replace(SOURCE, "return 0;", "return 0; }");
+        assert!(reparse_block(FileId::default(), &root(SOURCE), &edit).is_none());
+
+        let edit = replace(SOURCE, "return 0;", "return 0; {");
+        assert!(reparse_block(FileId::default(), &root(SOURCE), &edit).is_none());
+
+        // A string that runs on past the closing brace changes what follows
+        let edit = replace(SOURCE, "return 0;", "\"");
+        assert!(reparse_block(FileId::default(), &root(SOURCE), &edit).is_none());
+    }
+
+    #[test]
+    fn test_full_reparse_outside_blocks() {
+        for (target, insert) in [
+            ("int x", "int x, int z"),
+            ("function zero", "public function zero"),
+            ("import foo/bar;\n", ""),
+        ] {
+            let edit = replace(SOURCE, target, insert);
+            assert!(reparse_token(&root(SOURCE), &edit).is_none());
+            assert!(reparse_block(FileId::default(), &root(SOURCE), &edit).is_none());
+            check(SOURCE, &edit);
+        }
+    }
+
+    #[test]
+    fn test_every_single_character_deletion() {
+        // Whatever path is taken, the result must match a full parse
+        for (offset, c) in SOURCE.char_indices() {
+            let range = TextRange::at(TextSize::try_from(offset).unwrap(), TextSize::of(c));
+            let edit = TextEdit::delete(range);
+            let old = parse(SOURCE);
+            let new_text = edit.apply(SOURCE);
+            let tokens = bal_syntax::lexer::lex(FileId::default(), &new_text).tokens;
+            let full = Parser::new(FileId::default(), &new_text, tokens).parse();
+            let incremental = reparse(FileId::default(), &old, SOURCE, &edit);
+            assert_eq!(incremental, full.0, "deleting at {}", offset);
+        }
+    }
+}
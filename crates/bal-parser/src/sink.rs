@@ -1,29 +1,182 @@
 //! Event sink for building syntax trees
+//!
+//! Replays parser events into a `GreenNodeBuilder`, adding the trivia the
+//! parser skipped over. Whitespace between nodes stays in their parent.
+//! Comments directly above a declaration or statement, with no blank line
+//! in between, belong to that node, so documentation moves with what it
+//! documents.
 
 use crate::event::Event;
-use bal_syntax::BallerinaLanguage;
-use rowan::{GreenNodeBuilder, Language};
+use bal_syntax::token::Token;
+use bal_syntax::{BallerinaLanguage, SyntaxKind};
+use rowan::{GreenNode, GreenNodeBuilder, Language, NodeCache, TextRange};
 
-pub struct Sink {
-    builder: GreenNodeBuilder<'static>,
+pub struct Sink<'a> {
+    builder: GreenNodeBuilder<'a>,
+    source: &'a str,
+    tokens: &'a [Token],
+    cursor: usize,
+    depth: usize,
 }
 
-impl Sink {
-    pub fn new() -> Self {
+impl<'a> Sink<'a> {
+    pub fn new(source: &'a str, tokens: &'a [Token]) -> Self {
+        Self::with_builder(source, tokens, GreenNodeBuilder::new())
+    }
+
+    /// Build using a node cache shared with other trees
+    pub fn with_cache(source: &'a str, tokens: &'a [Token], cache: &'a mut NodeCache) -> Self {
+        Self::with_builder(source, tokens, GreenNodeBuilder::with_cache(cache))
+    }
+
+    fn with_builder(source: &'a str, tokens: &'a [Token], builder: GreenNodeBuilder<'a>) -> Self {
         Self {
-            builder: GreenNodeBuilder::new(),
+            builder,
+            source,
+            tokens,
+            cursor: 0,
+            depth: 0,
+        }
+    }
+
+    /// Build the tree described by `events`, which must hold exactly one
+    /// root node. Trivia after the last token goes at the end of the root.
+    pub fn finish(mut self, mut events: Vec<Event>) -> GreenNode {
+        let mut kinds = Vec::new();
+        for i in 0..events.len() {
+            match std::mem::replace(&mut events[i], Event::Tombstone) {
+                Event::Start {
+                    kind,
+                    forward_parent,
+                } => {
+                    // Start every forward parent first, outermost first
+                    kinds.push(kind);
+                    let mut pos = i;
+                    let mut forward_parent = forward_parent;
+                    while let Some(distance) = forward_parent {
+                        pos += distance;
+                        forward_parent = match std::mem::replace(&mut events[pos], Event::Tombstone)
+                        {
+                            Event::Start {
+                                kind,
+                                forward_parent,
+                            } => {
+                                kinds.push(kind);
+                                forward_parent
+                            }
+                            _ => unreachable!("a forward parent is always a start"),
+                        };
+                    }
+                    for kind in kinds.drain(..).rev() {
+                        self.start_node(kind);
+                    }
+                }
+                Event::Token => {
+                    self.add_tokens(self.pending_trivia());
+                    self.add_tokens(1);
+                }
+                Event::GluedToken { kind, parts } => {
+                    self.add_tokens(self.pending_trivia());
+                    self.add_glued_token(kind, parts);
+                }
+                Event::Finish => {
+                    self.depth -= 1;
+                    if self.depth == 0 {
+                        self.add_tokens(self.tokens.len() - self.cursor);
+                    }
+                    self.builder.finish_node();
+                }
+                Event::Tombstone => {}
+            }
+        }
+        self.builder.finish()
+    }
+
+    fn start_node(&mut self, kind: SyntaxKind) {
+        // The root's leading trivia is left for its first child to divide up
+        if self.depth > 0 {
+            let pending = self.pending_trivia();
+            let trivia = &self.tokens[self.cursor..self.cursor + pending];
+            let attached = attached_trivia(kind, trivia, self.source);
+            self.add_tokens(pending - attached);
+        }
+        self.builder
+            .start_node(BallerinaLanguage::kind_to_raw(kind));
+        self.depth += 1;
+        let pending = self.pending_trivia();
+        if self.depth > 1 {
+            self.add_tokens(pending);
         }
     }
 
-    pub fn process(&mut self, event: Event) {
-        match event {
-            Event::StartNode { kind } => self
-                .builder
-                .start_node(BallerinaLanguage::kind_to_raw(kind)),
-            Event::AddToken { kind, text } => self
-                .builder
-                .token(BallerinaLanguage::kind_to_raw(kind), text.as_str()),
-            Event::FinishNode => self.builder.finish_node(),
+    /// How many trivia tokens come before the next other token
+    fn pending_trivia(&self) -> usize {
+        self.tokens[self.cursor..]
+            .iter()
+            .take_while(|token| token.kind.is_trivia())
+            .count()
+    }
+
+    fn add_glued_token(&mut self, kind: SyntaxKind, parts: usize) {
+        let first = self.tokens[self.cursor].range;
+        let last = self.tokens[self.cursor + parts - 1].range;
+        let text = &self.source[TextRange::new(first.start(), last.end())];
+        self.builder
+            .token(BallerinaLanguage::kind_to_raw(kind), text);
+        self.cursor += parts;
+    }
+
+    fn add_tokens(&mut self, n: usize) {
+        for token in &self.tokens[self.cursor..self.cursor + n] {
+            self.builder.token(
+                BallerinaLanguage::kind_to_raw(token.kind),
+                token.text(self.source),
+            );
+        }
+        self.cursor += n;
+    }
+}
+
+/// How many of the trailing `trivia` belong to a node of `kind` starting
+/// right after them: the comments above it, up to the first blank line
+fn attached_trivia(kind: SyntaxKind, trivia: &[Token], source: &str) -> usize {
+    if !matches!(
+        kind,
+        SyntaxKind::IMPORT_DECL
+            | SyntaxKind::FUNCTION_DEF
+            | SyntaxKind::CONST_DECL
+            | SyntaxKind::MODULE_VAR_DECL
+            | SyntaxKind::TYPE_DEFINITION
+            | SyntaxKind::LOCAL_VAR_DECL_STMT
+            | SyntaxKind::FUNCTION_CALL_STMT
+            | SyntaxKind::ASSIGN_STMT
+            | SyntaxKind::RETURN_STMT
+            | SyntaxKind::IF_ELSE_STMT
+            | SyntaxKind::WHILE_STMT
+            | SyntaxKind::BREAK_STMT
+            | SyntaxKind::CONTINUE_STMT
+    ) {
+        return 0;
+    }
+
+    let mut attached = 0;
+    let mut line_breaks = 0;
+    for (i, token) in trivia.iter().rev().enumerate() {
+        match token.kind {
+            SyntaxKind::COMMENT | SyntaxKind::DOC_COMMENT => {
+                attached = i + 1;
+                line_breaks = 0;
+            }
+            SyntaxKind::NEWLINE => {
+                let text = token.text(source);
+                line_breaks += text.matches('\n').count() + text.matches('\r').count()
+                    - text.matches("\r\n").count();
+                if line_breaks > 1 {
+                    break;
+                }
+            }
+            _ => {}
         }
     }
+    attached
 }
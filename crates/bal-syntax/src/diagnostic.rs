@@ -0,0 +1,291 @@
+//! Diagnostics reported by every compiler phase
+//!
+//! A `Diagnostic` has a severity, a stable code, a message and, when it is
+//! about a piece of source text, a primary span. Secondary labels point at
+//! related code, and notes and help text give more context. Phases hand their
+//! diagnostics back to the caller, which decides how to show them.
+
+use crate::error::Span;
+use crate::render::Renderer;
+use crate::source::FileId;
+use crate::source::SourceDb;
+use crate::text_edit::TextEdit;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+    Hint,
+}
+
+/// Whether a fix can be applied without a person checking it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Applicability {
+    /// The fix is certainly what was meant; `bal fix` applies it
+    MachineApplicable,
+    /// The fix is a guess, offered to editors but never applied automatically
+    MaybeIncorrect,
+}
+
+/// A suggested change to the source that resolves a diagnostic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    /// What the fix does, such as "insert ';'"
+    pub message: String,
+    pub file: FileId,
+    /// Non-overlapping edits, all against the original text
+    pub edits: Vec<TextEdit>,
+    pub applicability: Applicability,
+}
+
+impl Fix {
+    pub fn new(message: impl Into<String>, file: FileId, edits: Vec<TextEdit>) -> Self {
+        Self {
+            message: message.into(),
+            file,
+            edits,
+            applicability: Applicability::MachineApplicable,
+        }
+    }
+
+    pub fn maybe_incorrect(mut self) -> Self {
+        self.applicability = Applicability::MaybeIncorrect;
+        self
+    }
+
+    pub fn is_machine_applicable(&self) -> bool {
+        self.applicability == Applicability::MachineApplicable
+    }
+}
+
+/// A span with a message explaining why it is relevant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Stable identifier such as `BCE2001`; see [`codes`]
+    pub code: &'static str,
+    pub message: String,
+    /// Where the problem is, if it is in source text
+    pub primary: Option<Label>,
+    /// Related locations, such as an earlier definition
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    pub help: Option<String>,
+    pub fixes: Vec<Fix>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            code,
+            message: message.into(),
+            primary: None,
+            labels: Vec::new(),
+            notes: Vec::new(),
+            help: None,
+            fixes: Vec::new(),
+        }
+    }
+
+    pub fn error(code: &'static str, message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, code, message)
+    }
+
+    pub fn warning(code: &'static str, message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, code, message)
+    }
+
+    pub fn hint(code: &'static str, message: impl Into<String>) -> Self {
+        Self::new(Severity::Hint, code, message)
+    }
+
+    pub fn with_primary(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.primary = Some(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    pub fn with_fix(mut self, fix: Fix) -> Self {
+        self.fixes.push(fix);
+        self
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        self.primary.as_ref().map(|label| label.span)
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Display the diagnostic with the source lines it points at, without color
+    pub fn display<'a>(&'a self, db: &'a SourceDb) -> impl fmt::Display + 'a {
+        WithSource {
+            diagnostic: self,
+            db,
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Hint => "hint",
+        })
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)?;
+        match &self.primary {
+            Some(label) if !label.message.is_empty() => write!(f, ": {}", label.message),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Collects the diagnostics of one or more phases for the caller
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, diagnostic: impl Into<Diagnostic>) {
+        self.diagnostics.push(diagnostic.into());
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.error_count() > 0
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.count(Severity::Error)
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.count(Severity::Warning)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    pub fn into_vec(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+
+    fn count(&self, severity: Severity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    }
+}
+
+impl Extend<Diagnostic> for Diagnostics {
+    fn extend<I: IntoIterator<Item = Diagnostic>>(&mut self, iter: I) {
+        self.diagnostics.extend(iter);
+    }
+}
+
+impl IntoIterator for Diagnostics {
+    type Item = Diagnostic;
+    type IntoIter = std::vec::IntoIter<Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.diagnostics.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+    type Item = &'a Diagnostic;
+    type IntoIter = std::slice::Iter<'a, Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Stable diagnostic codes. The first digit is the phase that reports them:
+/// 0 project loading, 1 lexing, 2 parsing and 3 semantic analysis.
+pub mod codes {
+    pub const PROJECT_IO: &str = "BCE0001";
+    pub const INVALID_MANIFEST: &str = "BCE0002";
+    pub const INVALID_PROJECT: &str = "BCE0003";
+
+    pub const INVALID_CHARACTER: &str = "BCE1001";
+    pub const UNTERMINATED_STRING: &str = "BCE1002";
+    pub const INVALID_ESCAPE: &str = "BCE1003";
+    pub const INVALID_NUMBER: &str = "BCE1004";
+    pub const UNEXPECTED_EOF: &str = "BCE1005";
+
+    pub const UNEXPECTED_TOKEN: &str = "BCE2001";
+    pub const MISSING_TOKEN: &str = "BCE2002";
+    pub const CHAINED_COMPARISON: &str = "BCE2003";
+    pub const TRAILING_INPUT: &str = "BCE2004";
+    pub const PARAMETER_ORDER: &str = "BCE2005";
+    pub const POSITIONAL_AFTER_NAMED: &str = "BCE2006";
+    pub const ASSIGNMENT_IN_CONDITION: &str = "BCE2007";
+
+    pub const DUPLICATE_DEFINITION: &str = "BCE3001";
+    pub const UNUSED_IMPORT: &str = "BCE3002";
+    pub const CYCLIC_INITIALIZATION: &str = "BCE3003";
+    pub const INVALID_ARGUMENTS: &str = "BCE3004";
+    pub const ARGUMENT_TYPE_MISMATCH: &str = "BCE3005";
+    pub const OPERATOR_TYPE_MISMATCH: &str = "BCE3006";
+}
+
+struct WithSource<'a> {
+    diagnostic: &'a Diagnostic,
+    db: &'a SourceDb,
+}
+
+impl fmt::Display for WithSource<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Renderer::new(false).write(f, self.db, self.diagnostic)
+    }
+}
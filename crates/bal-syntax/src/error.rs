@@ -1,19 +1,21 @@
+use crate::diagnostic::{codes, Diagnostic, Fix};
+use crate::project::ProjectError;
+use crate::source::FileId;
+use crate::text_edit::TextEdit;
+use crate::SyntaxKind;
+use rowan::TextRange;
 use std::fmt;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum LexerError {
-    InvalidCharacter {
-        character: char,
-        position: usize,
-        line: usize,
-        column: usize,
-        line_content: String,
-    },
-    UnterminatedString(usize),
+    InvalidCharacter { character: char, span: Span },
+    UnterminatedString { span: Span },
+    InvalidEscape { message: String, span: Span },
+    InvalidNumber { message: String, span: Span },
     UnexpectedEof,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ParserError {
     UnexpectedToken {
         expected: Vec<String>,
@@ -21,135 +23,207 @@ pub enum ParserError {
         span: Span,
     },
     MissingToken {
-        expected: String,
+        expected: SyntaxKind,
         after: String,
         span: Span,
     },
+    /// A second non-associative comparison, as in `a < b < c`
+    ChainedComparison { first: Span, span: Span },
+    /// More input after a complete fragment, such as a second expression
+    TrailingInput { fragment: String, span: Span },
+    /// A parameter of a kind that must come earlier, as in
+    /// `int a = 1, int b`; `kind` and `previous_kind` describe both
+    ParameterOrder {
+        kind: String,
+        previous_kind: String,
+        previous: Span,
+        span: Span,
+    },
+    /// A positional argument following a named one, as in `f(x = 1, 2)`
+    PositionalAfterNamed { named: Span, span: Span },
+    /// `=` where `==` was meant, as in `if x = 1 { }`; `span` is the `=`
+    AssignmentInCondition { span: Span },
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// A range of text in one source file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Span {
-    pub file: Option<String>,
-    pub start: usize,
-    pub end: usize,
-    pub line: usize,
-    pub column: usize,
-    pub line_content: Option<String>,
+    pub file: FileId,
+    pub range: TextRange,
 }
 
-impl fmt::Display for LexerError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            LexerError::InvalidCharacter {
-                character,
-                line,
-                column,
-                line_content,
-                ..
-            } => {
-                writeln!(f, "error: invalid character")?;
-                writeln!(f, " --> line {}:{}", line, column)?;
-                writeln!(f, "  |")?;
-                writeln!(f, "{} | {}", line, line_content)?;
-                writeln!(
-                    f,
-                    "  | {}^ unexpected character '{}'",
-                    " ".repeat(*column),
-                    if character.is_control() {
-                        format!("\\x{:02x}", *character as u8)
-                    } else {
-                        character.to_string()
-                    }
-                )
-            }
-            LexerError::UnterminatedString(pos) => {
-                writeln!(f, "error: unterminated string")?;
-                writeln!(f, "  |")?;
-                writeln!(
-                    f,
-                    "  | string starting at position {} is not terminated",
-                    pos
-                )
+impl Span {
+    pub fn new(file: FileId, range: TextRange) -> Self {
+        Self { file, range }
+    }
+
+    pub fn start(&self) -> usize {
+        self.range.start().into()
+    }
+
+    pub fn end(&self) -> usize {
+        self.range.end().into()
+    }
+}
+
+impl From<LexerError> for Diagnostic {
+    fn from(error: LexerError) -> Self {
+        let (code, title) = match &error {
+            LexerError::InvalidCharacter { .. } => (codes::INVALID_CHARACTER, "invalid character"),
+            LexerError::UnterminatedString { .. } => {
+                (codes::UNTERMINATED_STRING, "unterminated string")
             }
-            LexerError::UnexpectedEof => {
-                writeln!(f, "error: unexpected end of file")?;
-                writeln!(f, "  |")?;
-                writeln!(f, "  | the file ends unexpectedly")
+            LexerError::InvalidEscape { .. } => (codes::INVALID_ESCAPE, "invalid escape sequence"),
+            LexerError::InvalidNumber { .. } => (codes::INVALID_NUMBER, "invalid numeric literal"),
+            LexerError::UnexpectedEof => (codes::UNEXPECTED_EOF, "unexpected end of file"),
+        };
+        let label = match &error {
+            LexerError::InvalidCharacter { character, .. } => format!(
+                "unexpected character '{}'",
+                if character.is_control() {
+                    format!("\\x{:02x}", *character as u32)
+                } else {
+                    character.to_string()
+                }
+            ),
+            LexerError::UnterminatedString { .. } => {
+                "string starting here is not terminated".to_string()
             }
+            LexerError::InvalidEscape { message, .. }
+            | LexerError::InvalidNumber { message, .. } => message.clone(),
+            LexerError::UnexpectedEof => "the file ends unexpectedly".to_string(),
+        };
+
+        let diagnostic = Diagnostic::error(code, title);
+        match error.span() {
+            Some(span) => diagnostic.with_primary(*span, label),
+            None => diagnostic.with_note(label),
         }
     }
 }
 
-impl fmt::Display for ParserError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
+impl From<ParserError> for Diagnostic {
+    fn from(error: ParserError) -> Self {
+        match error {
             ParserError::UnexpectedToken {
                 expected,
                 found,
                 span,
             } => {
-                let file = span.file.as_deref().unwrap_or("unknown");
-                writeln!(f, "error: unexpected token")?;
-                writeln!(f, " --> {}:{}:{}", file, span.line, span.column + 1)?;
-                writeln!(f, "  |")?;
-                writeln!(
-                    f,
-                    "{} | {}",
-                    span.line,
-                    get_line_content(file, span.line).unwrap_or_default()
-                )?;
-                writeln!(
-                    f,
-                    "  | {}{} expected one of {:?}, found '{}'",
-                    " ".repeat(span.column),
-                    "^".repeat(found.len()),
-                    expected,
-                    found
-                )
+                let expected = match expected.as_slice() {
+                    [one] => one.clone(),
+                    many => format!("one of {}", many.join(", ")),
+                };
+                Diagnostic::error(codes::UNEXPECTED_TOKEN, "unexpected token")
+                    .with_primary(span, format!("expected {}, found {}", expected, found))
             }
             ParserError::MissingToken {
                 expected,
                 after,
                 span,
             } => {
-                let file = span.file.as_deref().unwrap_or("unknown");
-                writeln!(f, "error: missing token")?;
-                writeln!(f, " --> {}:{}:{}", file, span.line, span.column + 1)?;
-                writeln!(f, "  |")?;
-                writeln!(
-                    f,
-                    "{} | {}",
-                    span.line,
-                    get_line_content(file, span.line).unwrap_or_default()
-                )?;
-                writeln!(
-                    f,
-                    "  | {}{} missing '{}' after '{}'",
-                    " ".repeat(span.column),
-                    "^",
-                    expected,
-                    after
-                )
+                let diagnostic = Diagnostic::error(codes::MISSING_TOKEN, "missing token")
+                    .with_primary(span, format!("missing {} after {}", expected, after));
+                match expected.text() {
+                    Some(text) => diagnostic.with_fix(Fix::new(
+                        format!("insert {}", expected),
+                        span.file,
+                        vec![TextEdit::insert(span.range.start(), text)],
+                    )),
+                    None => diagnostic,
+                }
+            }
+            ParserError::ChainedComparison { first, span } => Diagnostic::error(
+                codes::CHAINED_COMPARISON,
+                "comparison operators cannot be chained",
+            )
+            .with_primary(span, "second comparison")
+            .with_label(first, "first comparison")
+            .with_help("compare each pair of operands separately"),
+            ParserError::TrailingInput { fragment, span } => {
+                Diagnostic::error(codes::TRAILING_INPUT, "unexpected trailing input")
+                    .with_primary(span, format!("expected end of input after {}", fragment))
+            }
+            ParserError::ParameterOrder {
+                kind,
+                previous_kind,
+                previous,
+                span,
+            } => Diagnostic::error(codes::PARAMETER_ORDER, "parameter out of order")
+                .with_primary(span, format!("{} after {}", kind, previous_kind))
+                .with_label(previous, format!("{} here", previous_kind))
+                .with_help(
+                    "required parameters come before defaultable ones, and the rest parameter comes last",
+                ),
+            ParserError::PositionalAfterNamed { named, span } => Diagnostic::error(
+                codes::POSITIONAL_AFTER_NAMED,
+                "positional argument after named argument",
+            )
+            .with_primary(span, "positional argument")
+            .with_label(named, "named argument")
+            .with_help("name this argument too, or move it before the named arguments"),
+            ParserError::AssignmentInCondition { span } => {
+                Diagnostic::error(codes::ASSIGNMENT_IN_CONDITION, "assignment in condition")
+                    .with_primary(span, "'=' assigns a value")
+                    .with_help("use '==' to compare values")
+                    .with_fix(Fix::new(
+                        "replace '=' with '=='",
+                        span.file,
+                        vec![TextEdit::replace(span.range, "==")],
+                    ))
             }
         }
     }
 }
 
-impl std::error::Error for LexerError {}
-impl std::error::Error for ParserError {}
-
-// Helper function to get the content of a specific line from a file
-fn get_line_content(file: &str, line_number: usize) -> std::io::Result<String> {
-    use std::fs::File;
-    use std::io::{BufRead, BufReader};
+impl From<&ProjectError> for Diagnostic {
+    fn from(error: &ProjectError) -> Self {
+        let code = match error {
+            ProjectError::Io(_) => codes::PROJECT_IO,
+            ProjectError::TomlParse(_) => codes::INVALID_MANIFEST,
+            ProjectError::Invalid(_) => codes::INVALID_PROJECT,
+        };
+        Diagnostic::error(code, error.to_string())
+    }
+}
 
-    let file = File::open(file)?;
-    let reader = BufReader::new(file);
+impl LexerError {
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            LexerError::InvalidCharacter { span, .. }
+            | LexerError::UnterminatedString { span }
+            | LexerError::InvalidEscape { span, .. }
+            | LexerError::InvalidNumber { span, .. } => Some(span),
+            LexerError::UnexpectedEof => None,
+        }
+    }
+}
 
-    for (index, line) in reader.lines().enumerate() {
-        if index + 1 == line_number {
-            return Ok(line?);
+impl ParserError {
+    pub fn span(&self) -> &Span {
+        match self {
+            ParserError::UnexpectedToken { span, .. }
+            | ParserError::MissingToken { span, .. }
+            | ParserError::ChainedComparison { span, .. }
+            | ParserError::TrailingInput { span, .. }
+            | ParserError::ParameterOrder { span, .. }
+            | ParserError::PositionalAfterNamed { span, .. }
+            | ParserError::AssignmentInCondition { span } => span,
         }
     }
-    Ok(String::new())
 }
+
+impl fmt::Display for LexerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Diagnostic::from(self.clone()).fmt(f)
+    }
+}
+
+impl fmt::Display for ParserError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Diagnostic::from(self.clone()).fmt(f)
+    }
+}
+
+impl std::error::Error for LexerError {}
+impl std::error::Error for ParserError {}
@@ -0,0 +1,104 @@
+//! Long-form explanations of diagnostic codes, as printed by `bal explain`
+//!
+//! Each explanation lives in `explanations/<code>.md` and shows an erroneous
+//! example followed by a corrected one. Every code in `diagnostic::codes`
+//! must have an entry here.
+
+use crate::diagnostic::codes;
+
+const EXPLANATIONS: &[(&str, &str)] = &[
+    (codes::PROJECT_IO, include_str!("explanations/BCE0001.md")),
+    (
+        codes::INVALID_MANIFEST,
+        include_str!("explanations/BCE0002.md"),
+    ),
+    (
+        codes::INVALID_PROJECT,
+        include_str!("explanations/BCE0003.md"),
+    ),
+    (
+        codes::INVALID_CHARACTER,
+        include_str!("explanations/BCE1001.md"),
+    ),
+    (
+        codes::UNTERMINATED_STRING,
+        include_str!("explanations/BCE1002.md"),
+    ),
+    (
+        codes::INVALID_ESCAPE,
+        include_str!("explanations/BCE1003.md"),
+    ),
+    (
+        codes::INVALID_NUMBER,
+        include_str!("explanations/BCE1004.md"),
+    ),
+    (
+        codes::UNEXPECTED_EOF,
+        include_str!("explanations/BCE1005.md"),
+    ),
+    (
+        codes::UNEXPECTED_TOKEN,
+        include_str!("explanations/BCE2001.md"),
+    ),
+    (
+        codes::MISSING_TOKEN,
+        include_str!("explanations/BCE2002.md"),
+    ),
+    (
+        codes::CHAINED_COMPARISON,
+        include_str!("explanations/BCE2003.md"),
+    ),
+    (
+        codes::TRAILING_INPUT,
+        include_str!("explanations/BCE2004.md"),
+    ),
+    (
+        codes::PARAMETER_ORDER,
+        include_str!("explanations/BCE2005.md"),
+    ),
+    (
+        codes::POSITIONAL_AFTER_NAMED,
+        include_str!("explanations/BCE2006.md"),
+    ),
+    (
+        codes::ASSIGNMENT_IN_CONDITION,
+        include_str!("explanations/BCE2007.md"),
+    ),
+    (
+        codes::DUPLICATE_DEFINITION,
+        include_str!("explanations/BCE3001.md"),
+    ),
+    (
+        codes::UNUSED_IMPORT,
+        include_str!("explanations/BCE3002.md"),
+    ),
+    (
+        codes::CYCLIC_INITIALIZATION,
+        include_str!("explanations/BCE3003.md"),
+    ),
+    (
+        codes::INVALID_ARGUMENTS,
+        include_str!("explanations/BCE3004.md"),
+    ),
+    (
+        codes::ARGUMENT_TYPE_MISMATCH,
+        include_str!("explanations/BCE3005.md"),
+    ),
+    (
+        codes::OPERATOR_TYPE_MISMATCH,
+        include_str!("explanations/BCE3006.md"),
+    ),
+];
+
+/// The explanation of `code`, which may be written in lower case
+pub fn explain(code: &str) -> Option<&'static str> {
+    EXPLANATIONS
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(code.trim()))
+        .map(|&(_, text)| text)
+}
+
+/// Every code that has an explanation, in order
+pub fn registered_codes() -> impl Iterator<Item = &'static str> {
+    EXPLANATIONS.iter().map(|&(code, _)| code)
+}
@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod tests {
+    use crate::explain::{explain, registered_codes};
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    #[test]
+    fn test_explain() {
+        assert!(explain("BCE2002").unwrap().contains("import ballerina/io;"));
+        assert_eq!(explain("bce2002"), explain("BCE2002"));
+        assert_eq!(explain("BCE9999"), None);
+    }
+
+    #[test]
+    fn test_explanations_have_examples() {
+        let codes: Vec<_> = registered_codes().collect();
+        let mut sorted = codes.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(codes, sorted, "codes should be unique and in order");
+
+        for code in codes {
+            let text = explain(code).unwrap();
+            let erroneous = text.find("Erroneous example");
+            let corrected = text.find("Corrected example");
+            assert!(
+                erroneous.is_some() && erroneous < corrected,
+                "{} should show an erroneous example, then a corrected one",
+                code
+            );
+            assert_eq!(text.matches("```").count(), 4, "{}", code);
+        }
+    }
+
+    /// Scans the compiler's sources for uses of `codes` constants and `BCE`
+    /// literals, so a new code cannot ship without an explanation
+    #[test]
+    fn test_every_emitted_code_is_registered() {
+        let crates = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let mut sources = Vec::new();
+        collect_sources(&crates, &mut sources);
+
+        let diagnostic_rs = include_str!("diagnostic.rs");
+        let constants: HashMap<&str, &str> = diagnostic_rs
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("pub const "))
+            .filter_map(|line| {
+                let (name, value) = line.split_once(": &str = ")?;
+                Some((name, value.trim_end_matches(';').trim_matches('"')))
+            })
+            .collect();
+        assert!(!constants.is_empty());
+
+        let registered: Vec<_> = registered_codes().collect();
+        for (name, code) in &constants {
+            assert!(
+                registered.contains(code),
+                "{} ({}) has no explanation",
+                name,
+                code
+            );
+        }
+
+        for (path, text) in &sources {
+            for (i, _) in text.match_indices("codes::") {
+                let name: String = text[i + "codes::".len()..]
+                    .chars()
+                    .take_while(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || *c == '_')
+                    .collect();
+                if name.is_empty() {
+                    continue;
+                }
+                assert!(
+                    constants.contains_key(name.as_str()),
+                    "{} uses unknown code codes::{}",
+                    path,
+                    name
+                );
+            }
+            for (i, _) in text.match_indices("\"BCE") {
+                let code = &text[i + 1..(i + 8).min(text.len())];
+                if code[3..].chars().all(|c| c.is_ascii_digit()) {
+                    assert!(
+                        registered.contains(&code),
+                        "{} emits unregistered {}",
+                        path,
+                        code
+                    );
+                }
+            }
+        }
+    }
+
+    fn collect_sources(dir: &Path, sources: &mut Vec<(String, String)>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().into_owned();
+            // Tests make up codes of their own
+            if path.is_dir() {
+                if name != "target" && name != "tests" {
+                    collect_sources(&path, sources);
+                }
+            } else if name.ends_with(".rs") && !name.ends_with("_test.rs") {
+                if let Ok(text) = std::fs::read_to_string(&path) {
+                    sources.push((path.display().to_string(), text));
+                }
+            }
+        }
+    }
+}
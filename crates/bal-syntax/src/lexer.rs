@@ -1,18 +1,26 @@
 //! Lexer implementation for Ballerina
 
+use crate::diagnostic::Diagnostic;
 use crate::error::LexerError;
 use crate::error::Span;
+use crate::literal;
+use crate::source::FileId;
+use crate::token;
+use crate::SyntaxKind;
 use logos::Logos; // Use Span from error module
+use rowan::{TextRange, TextSize};
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct TokenInfo {
+pub struct TokenInfo<'a> {
     pub kind: Token,
-    pub text: String,
+    pub text: &'a str,
     pub span: Span,
 }
 
 #[derive(Logos, Debug, PartialEq, Clone)]
-#[logos(skip r"[ \t\f]+")] // Skip non-newline whitespace
+#[logos(subpattern ident_initial = r"[A-Za-z_\p{L}\p{Nl}]")]
+#[logos(subpattern ident_following = r"[A-Za-z0-9_\p{L}\p{Nl}\p{Mn}\p{Mc}\p{Nd}\p{Pc}]")]
+#[logos(subpattern ident_escape = r"\\[^A-Za-z\s]|\\u\{[0-9a-fA-F]+\}")]
 pub enum Token {
     // Keywords
     #[token("import")]
@@ -33,6 +41,12 @@ pub enum Token {
     #[token("boolean")]
     Boolean,
 
+    #[token("float")]
+    Float,
+
+    #[token("decimal")]
+    Decimal,
+
     #[token("if")]
     If,
 
@@ -57,6 +71,45 @@ pub enum Token {
     #[token("false")]
     False,
 
+    #[token("as")]
+    As,
+
+    #[token("const")]
+    Const,
+
+    #[token("final")]
+    Final,
+
+    #[token("configurable")]
+    Configurable,
+
+    #[token("type")]
+    Type,
+
+    #[token("string")]
+    String,
+
+    #[token("any")]
+    Any,
+
+    #[token("anydata")]
+    Anydata,
+
+    #[token("json")]
+    Json,
+
+    #[token("never")]
+    Never,
+
+    #[token("readonly")]
+    Readonly,
+
+    #[token("map")]
+    Map,
+
+    #[token("record")]
+    Record,
+
     // Operators
     #[token("==")]
     Eq,
@@ -91,9 +144,41 @@ pub enum Token {
     #[token("%")]
     Percent,
 
+    #[token("!")]
+    Bang,
+
     #[token("=")]
     Assign,
 
+    #[token("|")]
+    Pipe,
+
+    #[token("===")]
+    EqEqEq,
+
+    #[token("!==")]
+    NotEqEq,
+
+    #[token("&&")]
+    AmpAmp,
+
+    #[token("||")]
+    PipePipe,
+
+    #[token("&")]
+    Amp,
+
+    #[token("^")]
+    Caret,
+
+    #[token("~")]
+    Tilde,
+
+    // There is no `>>` token, so `map<map<int>>` closes two type
+    // parameters; the parser glues adjacent `>`s into shift operators
+    #[token("<<")]
+    LtLt,
+
     // Delimiters
     #[token("(")]
     LParen,
@@ -107,101 +192,188 @@ pub enum Token {
     #[token("}")]
     RBrace,
 
+    #[token("[")]
+    LBracket,
+
+    #[token("]")]
+    RBracket,
+
+    // Delimit closed record types, as in `record {| int x; |}`
+    #[token("{|")]
+    LBracePipe,
+
+    #[token("|}")]
+    PipeRBrace,
+
     #[token(",")]
     Comma,
 
     #[token(":")]
     Colon,
 
+    #[token(".")]
+    Dot,
+
+    #[token("...")]
+    Ellipsis,
+
+    #[token("?")]
+    Question,
+
     #[token(";")]
     Semicolon,
 
     // Identifiers and literals
-    #[regex("[A-Za-z][A-Za-z0-9_]*")]
+    // Unquoted identifiers may use Unicode letters and `\`-escaped characters;
+    // a leading `'` quotes an identifier so that it can spell a keyword
+    #[regex(r"((?&ident_initial)|(?&ident_escape))((?&ident_following)|(?&ident_escape))*")]
+    #[regex(r"'((?&ident_following)|(?&ident_escape))+")]
     Identifier,
 
     #[regex("0|[1-9][0-9]*")]
+    #[regex("0[xX][0-9a-fA-F]+")]
     IntegerLiteral,
 
-    #[regex(r"\n+")]
+    // Decimal floating point; an `f` suffix makes it a float, `d` a decimal
+    #[regex(r"(0|[1-9][0-9]*)?\.[0-9]+([eE][+-]?[0-9]+)?[fFdD]?")]
+    #[regex(r"(0|[1-9][0-9]*)[eE][+-]?[0-9]+[fFdD]?")]
+    #[regex(r"(0|[1-9][0-9]*)[fFdD]")]
+    FloatingPointLiteral,
+
+    #[regex(r#""([^"\\\r\n]|\\[^\r\n])*""#)]
+    StringLiteral,
+
+    // A string literal missing its closing quote before the end of the line
+    #[regex(r#""([^"\\\r\n]|\\[^\r\n])*\\?"#)]
+    UnterminatedString,
+
+    // Trivia: kept as tokens so the syntax tree reproduces the source exactly
+    // Includes the byte order mark some editors write at the start of a file
+    #[regex(r"[ \t\f\u{FEFF}]+")]
+    Whitespace,
+
+    #[regex(r"(\r\n|\r|\n)+")]
     Newline,
 
-    #[regex(r"//[^\n]*")]
+    #[regex(r"//[^\r\n]*")]
     LineComment,
+
+    // A `#` documentation line; consecutive lines document the next declaration
+    #[regex(r"#[^\r\n]*")]
+    DocComment,
+}
+
+/// A token stream that covers every byte of the input, plus the diagnostics
+/// found while producing it. Unrecognized input becomes `SyntaxKind::ERROR`.
+#[derive(Debug, Default)]
+pub struct LexResult {
+    pub tokens: Vec<token::Token>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Lex a whole source file without dropping any input
+pub fn lex(file: FileId, source: &str) -> LexResult {
+    let mut lexer = Lexer::new(file, source);
+    let mut result = LexResult::default();
+
+    while let Some(token) = lexer.next_token() {
+        match token {
+            Ok(token_info) => {
+                let start = token_info.span.start();
+                match token_info.kind {
+                    Token::StringLiteral | Token::Identifier => {
+                        let value = if token_info.kind == Token::StringLiteral {
+                            literal::string_value(token_info.text)
+                        } else {
+                            literal::identifier_value(token_info.text)
+                        };
+                        if let Err(escapes) = value {
+                            result.diagnostics.extend(escapes.into_iter().map(|e| {
+                                Diagnostic::from(LexerError::InvalidEscape {
+                                    message: e.message,
+                                    span: lexer.span(start + e.range.start..start + e.range.end),
+                                })
+                            }));
+                        }
+                    }
+                    Token::IntegerLiteral | Token::FloatingPointLiteral => {
+                        // Whether the int minimum's magnitude is negated
+                        // depends on the tree, so semantic analysis checks it
+                        if let (Err(message), false) = (
+                            literal::numeric_value(token_info.text),
+                            literal::is_int_min_magnitude(token_info.text),
+                        ) {
+                            result.diagnostics.push(
+                                LexerError::InvalidNumber {
+                                    message,
+                                    span: token_info.span,
+                                }
+                                .into(),
+                            );
+                        }
+                    }
+                    _ => {}
+                }
+                result.tokens.push(token::Token::new(
+                    SyntaxKind::from(token_info.kind),
+                    token_info.span.range,
+                ));
+            }
+            Err(error) => {
+                let span = lexer.span(lexer.inner.span());
+                result
+                    .tokens
+                    .push(token::Token::new(SyntaxKind::ERROR, span.range));
+                result.diagnostics.push(error.into());
+            }
+        }
+    }
+
+    result
 }
 
 pub struct Lexer<'a> {
     inner: logos::Lexer<'a, Token>,
     source: &'a str,
-    line_starts: Vec<usize>, // Cache line start positions
+    file: FileId,
 }
 
 impl<'a> Lexer<'a> {
-    pub fn new(input: &'a str) -> Self {
-        // Calculate line starts once during initialization
-        let line_starts: Vec<_> = std::iter::once(0)
-            .chain(input.match_indices('\n').map(|(i, _)| i + 1))
-            .collect();
-
+    pub fn new(file: FileId, input: &'a str) -> Self {
         Self {
             inner: Token::lexer(input),
             source: input,
-            line_starts,
-        }
-    }
-
-    fn get_position(&self, offset: usize) -> (usize, usize) {
-        match self.line_starts.binary_search(&offset) {
-            Ok(line) => (line + 1, 0),
-            Err(line) => (line, offset - self.line_starts[line - 1]),
+            file,
         }
     }
 
-    pub fn next_token(&mut self) -> Option<Result<TokenInfo, LexerError>> {
+    pub fn next_token(&mut self) -> Option<Result<TokenInfo<'a>, LexerError>> {
         self.inner.next().map(|token| {
             let range = self.inner.span();
-            let (line, column) = self.get_position(range.start);
-
-            token
-                .map_err(|_| {
-                    // Get the line content for error reporting
-                    let line_content = self.get_line_content(line);
-
-                    LexerError::InvalidCharacter {
-                        character: self.source[range.start..].chars().next().unwrap_or('\0'),
-                        position: range.start,
-                        line,
-                        column,
-                        line_content,
-                    }
-                })
-                .map(|token| TokenInfo {
+            let span = self.span(range.clone());
+
+            match token {
+                Ok(Token::UnterminatedString) => Err(LexerError::UnterminatedString { span }),
+                Ok(token) => Ok(TokenInfo {
                     kind: token,
-                    text: self.source[range.clone()].to_string(),
-                    span: Span {
-                        file: None,
-                        start: range.start,
-                        end: range.end,
-                        line,
-                        column,
-                        line_content: None,
-                    },
-                })
+                    text: &self.source[range],
+                    span,
+                }),
+                Err(_) => Err(LexerError::InvalidCharacter {
+                    character: self.source[range.start..].chars().next().unwrap_or('\0'),
+                    span,
+                }),
+            }
         })
     }
 
-    fn get_line_content(&self, line_number: usize) -> String {
-        let start = if line_number > 0 {
-            self.line_starts[line_number - 1]
-        } else {
-            0
-        };
-        let end = self
-            .line_starts
-            .get(line_number)
-            .copied()
-            .unwrap_or(self.source.len());
-
-        self.source[start..end].trim_end().to_string()
+    fn span(&self, range: std::ops::Range<usize>) -> Span {
+        Span::new(
+            self.file,
+            TextRange::new(
+                TextSize::try_from(range.start).unwrap(),
+                TextSize::try_from(range.end).unwrap(),
+            ),
+        )
     }
 }
@@ -1,14 +1,21 @@
 #[cfg(test)]
 mod tests {
-    use crate::lexer::{Lexer, Token, TokenInfo};
+    use crate::diagnostic::{codes, Diagnostic};
+    use crate::lexer::{lex, Lexer, Token, TokenInfo};
+    use crate::line_index::{LineCol, LineIndex};
+    use crate::literal::{
+        identifier_value, is_int_min_magnitude, numeric_value, string_value, NumericValue,
+    };
+    use crate::source::FileId;
+    use crate::SyntaxKind;
 
-    fn tokenize(input: &str) -> Vec<TokenInfo> {
-        let mut lexer = Lexer::new(input);
+    fn tokenize(input: &str) -> Vec<TokenInfo<'_>> {
+        let mut lexer = Lexer::new(FileId::default(), input);
         let mut tokens = Vec::new();
         while let Some(result) = lexer.next_token() {
             if let Ok(token) = result {
-                // Skip newlines
-                if !matches!(token.kind, Token::Newline) {
+                // Skip trivia
+                if !matches!(token.kind, Token::Whitespace | Token::Newline) {
                     tokens.push(token);
                 }
             }
@@ -16,9 +23,15 @@ mod tests {
         tokens
     }
 
+    fn location(diagnostic: &Diagnostic) -> (&'static str, usize, usize) {
+        let span = diagnostic.span().expect("lexer diagnostics have a span");
+        (diagnostic.code, span.start(), span.end())
+    }
+
     #[test]
     fn test_keywords() {
-        let input = "import public function returns int boolean if else while break continue return true false";
+        let input = "import public function returns int boolean float decimal if else while break continue return true false as const final configurable type \
+                     string any anydata json never readonly map record";
         let tokens: Vec<_> = tokenize(input).into_iter().map(|t| t.kind).collect();
         assert_eq!(
             tokens,
@@ -29,6 +42,8 @@ mod tests {
                 Token::Returns,
                 Token::Int,
                 Token::Boolean,
+                Token::Float,
+                Token::Decimal,
                 Token::If,
                 Token::Else,
                 Token::While,
@@ -37,13 +52,26 @@ mod tests {
                 Token::Return,
                 Token::True,
                 Token::False,
+                Token::As,
+                Token::Const,
+                Token::Final,
+                Token::Configurable,
+                Token::Type,
+                Token::String,
+                Token::Any,
+                Token::Anydata,
+                Token::Json,
+                Token::Never,
+                Token::Readonly,
+                Token::Map,
+                Token::Record,
             ]
         );
     }
 
     #[test]
     fn test_operators() {
-        let input = "== != < <= > >= + - * / % =";
+        let input = "== != < <= > >= + - * / % ! = | === !== && || & ^ ~ << >>";
         let tokens: Vec<_> = tokenize(input).into_iter().map(|t| t.kind).collect();
         assert_eq!(
             tokens,
@@ -59,14 +87,26 @@ mod tests {
                 Token::Star,
                 Token::Slash,
                 Token::Percent,
+                Token::Bang,
                 Token::Assign,
+                Token::Pipe,
+                Token::EqEqEq,
+                Token::NotEqEq,
+                Token::AmpAmp,
+                Token::PipePipe,
+                Token::Amp,
+                Token::Caret,
+                Token::Tilde,
+                Token::LtLt,
+                Token::Gt,
+                Token::Gt,
             ]
         );
     }
 
     #[test]
     fn test_delimiters() {
-        let input = "( ) { } , : ;";
+        let input = "( ) { } [ ] {| |} , : . ... ? ;";
         let tokens: Vec<_> = tokenize(input).into_iter().map(|t| t.kind).collect();
         assert_eq!(
             tokens,
@@ -75,8 +115,15 @@ mod tests {
                 Token::RParen,
                 Token::LBrace,
                 Token::RBrace,
+                Token::LBracket,
+                Token::RBracket,
+                Token::LBracePipe,
+                Token::PipeRBrace,
                 Token::Comma,
                 Token::Colon,
+                Token::Dot,
+                Token::Ellipsis,
+                Token::Question,
                 Token::Semicolon,
             ]
         );
@@ -92,6 +139,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_extended_identifiers() {
+        let input = r"_tmp 'int 'function a\-b \u{61}b café 名前 x_1 'if2";
+        let tokens: Vec<_> = tokenize(input)
+            .into_iter()
+            .map(|t| (t.kind, t.text))
+            .collect();
+        let expected: Vec<_> = input
+            .split(' ')
+            .map(|text| (Token::Identifier, text))
+            .collect();
+        assert_eq!(tokens, expected);
+    }
+
+    #[test]
+    fn test_identifier_values() {
+        assert_eq!(identifier_value("foo").unwrap(), "foo");
+        assert_eq!(identifier_value("'int").unwrap(), "int");
+        assert_eq!(identifier_value(r"a\-b").unwrap(), "a-b");
+        assert_eq!(identifier_value(r"'\u{61}\ b").unwrap(), "a b");
+        let errors = identifier_value(r"'x\u{D800}").unwrap_err();
+        assert_eq!(errors[0].range, 2..10);
+    }
+
     #[test]
     fn test_integer_literals() {
         let input = "0 42 123456789";
@@ -129,7 +200,7 @@ mod tests {
             .find(|t| matches!(t.kind, Token::LineComment))
             .unwrap();
         assert_eq!(token.text, "// comment");
-        assert_eq!((token.span.start, token.span.end), (4, 14));
+        assert_eq!((token.span.start(), token.span.end()), (4, 14));
     }
 
     #[test]
@@ -182,6 +253,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_qualified_import_statement() {
+        let input = "import ballerina/lang.'int as ints;";
+        let tokens: Vec<_> = tokenize(input).into_iter().map(|t| t.kind).collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Import,
+                Token::Identifier,
+                Token::Slash,
+                Token::Identifier,
+                Token::Dot,
+                Token::Identifier,
+                Token::As,
+                Token::Identifier,
+                Token::Semicolon,
+            ]
+        );
+    }
+
     #[test]
     fn test_if_else_statement() {
         let input = r#"
@@ -262,14 +353,253 @@ mod tests {
     fn test_token_position() {
         let input = "foo\nbar // comment\nbaz";
         let tokens = tokenize(input);
+        let index = LineIndex::new(input);
+        let position = |token: &TokenInfo| index.line_col(token.span.range.start());
+
+        assert_eq!(position(&tokens[0]), LineCol { line: 0, col: 0 });
+        assert_eq!(position(&tokens[1]), LineCol { line: 1, col: 0 });
+        assert_eq!(position(&tokens[2]), LineCol { line: 1, col: 4 });
+    }
+
+    #[test]
+    fn test_trivia_tokens() {
+        let input = "\u{FEFF}a \t b\r\n\n// c\r\n\r";
+        let mut lexer = Lexer::new(FileId::default(), input);
+        let mut tokens = Vec::new();
+        while let Some(Ok(token)) = lexer.next_token() {
+            tokens.push((token.kind, token.text));
+        }
+        assert_eq!(
+            tokens,
+            vec![
+                (Token::Whitespace, "\u{FEFF}"),
+                (Token::Identifier, "a"),
+                (Token::Whitespace, " \t "),
+                (Token::Identifier, "b"),
+                (Token::Newline, "\r\n\n"),
+                (Token::LineComment, "// c"),
+                (Token::Newline, "\r\n\r"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fixed_tokens_agree_with_lexer() {
+        for (kind, text) in SyntaxKind::fixed_tokens() {
+            let tokens = tokenize(text);
+            if matches!(kind, SyntaxKind::GT_GT | SyntaxKind::GT_GT_GT) {
+                // Glued by the parser, never lexed
+                assert!(tokens.iter().all(|token| token.kind == Token::Gt));
+                assert_eq!(kind.text(), Some(text));
+                continue;
+            }
+            assert_eq!(tokens.len(), 1, "'{}' should lex as one token", text);
+            assert_eq!(SyntaxKind::from(tokens[0].kind.clone()), kind);
+            assert_eq!(kind.text(), Some(text));
+        }
+    }
+
+    #[test]
+    fn test_kind_classification() {
+        assert!(SyntaxKind::WHILE_KW.is_keyword());
+        assert!(SyntaxKind::BANG.is_operator());
+        assert!(SyntaxKind::SEMICOLON.is_delimiter());
+        assert!(SyntaxKind::NEWLINE.is_trivia());
+        assert!(!SyntaxKind::IDENTIFIER.is_keyword());
+        assert_eq!(SyntaxKind::IDENTIFIER.text(), None);
+        assert_eq!(
+            SyntaxKind::from_keyword("return"),
+            Some(SyntaxKind::RETURN_KW)
+        );
+        assert_eq!(SyntaxKind::from_keyword("=="), None);
+    }
+
+    #[test]
+    fn test_not_operator() {
+        let tokens: Vec<_> = tokenize("!x != y").into_iter().map(|t| t.kind).collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Bang,
+                Token::Identifier,
+                Token::NotEq,
+                Token::Identifier
+            ]
+        );
+    }
 
-        assert_eq!(tokens[0].span.line, 1);
-        assert_eq!(tokens[0].span.column, 0);
+    #[test]
+    fn test_lex_covers_invalid_input() {
+        let input = "int $x = 1;\nfoo § @";
+        let result = lex(FileId::default(), input);
+
+        let text: String = result
+            .tokens
+            .iter()
+            .map(|token| token.text(input))
+            .collect();
+        assert_eq!(text, input);
+
+        let errors: Vec<_> = result
+            .tokens
+            .iter()
+            .filter(|token| token.kind == SyntaxKind::ERROR)
+            .map(|token| {
+                let range = token.range;
+                (token.text(input), range.start().into(), range.end().into())
+            })
+            .collect();
+        assert_eq!(
+            errors,
+            vec![("$", 4u32, 5u32), ("§", 16, 18), ("@", 19, 20)]
+        );
+
+        assert_eq!(result.diagnostics.len(), 3);
+        assert_eq!(result.diagnostics[0].code, codes::INVALID_CHARACTER);
+        assert_eq!(
+            result.diagnostics[0].to_string(),
+            "invalid character: unexpected character '$'"
+        );
+    }
+
+    #[test]
+    fn test_string_literals() {
+        let input = r#"io:println("hello, world"); "a\"b" """#;
+        let tokens: Vec<_> = tokenize(input)
+            .into_iter()
+            .filter(|t| t.kind == Token::StringLiteral)
+            .map(|t| t.text)
+            .collect();
+        assert_eq!(tokens, vec![r#""hello, world""#, r#""a\"b""#, r#""""#]);
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        assert_eq!(
+            string_value(r#""tab\tnl\ncr\rbs\\q\"""#).unwrap(),
+            "tab\tnl\ncr\rbs\\q\""
+        );
+        assert_eq!(string_value(r#""\u{48}\u{1F600}""#).unwrap(), "H\u{1F600}");
+
+        let errors = string_value(r#""a\qb\u{D800}\u{}""#).unwrap_err();
+        let ranges: Vec<_> = errors.iter().map(|e| e.range.clone()).collect();
+        assert_eq!(ranges, vec![2..4, 5..13, 13..17]);
+        assert!(errors[0].message.contains("'\\q'"));
+    }
 
-        assert_eq!(tokens[1].span.line, 2);
-        assert_eq!(tokens[1].span.column, 0);
+    #[test]
+    fn test_invalid_escape_span() {
+        let result = lex(FileId::default(), "x = \"ok\\z\";");
+        assert_eq!(result.tokens[4].kind, SyntaxKind::STRING_LITERAL);
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(
+            location(&result.diagnostics[0]),
+            (codes::INVALID_ESCAPE, 7, 9)
+        );
+    }
+
+    #[test]
+    fn test_unterminated_string() {
+        let input = "x = \"abc\ny;";
+        let result = lex(FileId::default(), input);
+
+        let text: String = result
+            .tokens
+            .iter()
+            .map(|token| token.text(input))
+            .collect();
+        assert_eq!(text, input);
+        assert_eq!(result.tokens[4].kind, SyntaxKind::ERROR);
+        assert_eq!(result.tokens[4].text(input), "\"abc");
+
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(
+            location(&result.diagnostics[0]),
+            (codes::UNTERMINATED_STRING, 4, 8)
+        );
+    }
 
-        assert_eq!(tokens[2].span.line, 2);
-        assert_eq!(tokens[2].span.column, 4);
+    #[test]
+    fn test_numeric_literals() {
+        let input = "0 42 0xFF 0X1a 1.5 .25 3.0e-2 1E10 2f 2.5F 1.0d 7D";
+        let tokens: Vec<_> = tokenize(input)
+            .into_iter()
+            .map(|t| (t.kind, t.text))
+            .collect();
+        let int = |text| (Token::IntegerLiteral, text);
+        let float = |text| (Token::FloatingPointLiteral, text);
+        assert_eq!(
+            tokens,
+            vec![
+                int("0"),
+                int("42"),
+                int("0xFF"),
+                int("0X1a"),
+                float("1.5"),
+                float(".25"),
+                float("3.0e-2"),
+                float("1E10"),
+                float("2f"),
+                float("2.5F"),
+                float("1.0d"),
+                float("7D"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_numeric_values() {
+        assert_eq!(numeric_value("0xFF"), Ok(NumericValue::Int(255)));
+        assert_eq!(
+            numeric_value("9223372036854775807"),
+            Ok(NumericValue::Int(i64::MAX))
+        );
+        assert_eq!(numeric_value("2.5e1f"), Ok(NumericValue::Float(25.0)));
+        assert_eq!(numeric_value(".5"), Ok(NumericValue::Float(0.5)));
+        assert_eq!(
+            numeric_value("0.10d"),
+            Ok(NumericValue::Decimal("0.10".to_string()))
+        );
+        assert!(numeric_value("9223372036854775808").is_err());
+        assert!(numeric_value("0x10000000000000000").is_err());
+        assert!(numeric_value("1e400").is_err());
+    }
+
+    #[test]
+    fn test_int_literal_out_of_range() {
+        let result = lex(FileId::default(), "int x = 99999999999999999999;");
+        assert_eq!(result.diagnostics.len(), 1);
+        let diagnostic = &result.diagnostics[0];
+        assert_eq!(location(diagnostic), (codes::INVALID_NUMBER, 8, 28));
+        assert!(diagnostic.to_string().contains("out of range"));
+        assert_eq!(result.tokens[6].kind, SyntaxKind::INTEGER_LITERAL);
+    }
+
+    #[test]
+    fn test_int_min_magnitude_is_left_to_semantic_analysis() {
+        assert!(is_int_min_magnitude("9223372036854775808"));
+        assert!(is_int_min_magnitude("0x8000000000000000"));
+        assert!(!is_int_min_magnitude("9223372036854775809"));
+        let result = lex(FileId::default(), "int x = -9223372036854775808;");
+        assert!(result.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_doc_comments() {
+        let input = "# Summary\n# + x - value\nfunction f(int x) { } // done";
+        let tokens: Vec<_> = tokenize(input)
+            .into_iter()
+            .filter(|t| matches!(t.kind, Token::DocComment | Token::LineComment))
+            .map(|t| (t.kind, t.text))
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                (Token::DocComment, "# Summary"),
+                (Token::DocComment, "# + x - value"),
+                (Token::LineComment, "// done"),
+            ]
+        );
+        assert!(SyntaxKind::DOC_COMMENT.is_trivia());
     }
 }
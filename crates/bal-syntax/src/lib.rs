@@ -11,8 +11,11 @@ pub enum SyntaxKind {
     // Tokens
     WHITESPACE,
     COMMENT,
+    DOC_COMMENT,
     IDENTIFIER,
     INTEGER_LITERAL,
+    FLOATING_POINT_LITERAL,
+    STRING_LITERAL,
     NEWLINE,
 
     // Keywords
@@ -22,6 +25,8 @@ pub enum SyntaxKind {
     RETURNS_KW,
     INT_KW,
     BOOLEAN_KW,
+    FLOAT_KW,
+    DECIMAL_KW,
     IF_KW,
     ELSE_KW,
     WHILE_KW,
@@ -30,40 +35,102 @@ pub enum SyntaxKind {
     RETURN_KW,
     TRUE_KW,
     FALSE_KW,
+    AS_KW,
+    CONST_KW,
+    FINAL_KW,
+    CONFIGURABLE_KW,
+    TYPE_KW,
+    STRING_KW,
+    ANY_KW,
+    ANYDATA_KW,
+    JSON_KW,
+    NEVER_KW,
+    READONLY_KW,
+    MAP_KW,
+    RECORD_KW,
 
     // Operators
-    EQ,      // ==
-    NOT_EQ,  // !=
-    LT,      // <
-    LT_EQ,   // <=
-    GT,      // >
-    GT_EQ,   // >=
-    PLUS,    // +
-    MINUS,   // -
-    STAR,    // *
-    SLASH,   // /
-    PERCENT, // %
-    ASSIGN,  // =
+    EQ,           // ==
+    NOT_EQ,       // !=
+    LT,           // <
+    LT_EQ,        // <=
+    GT,           // >
+    GT_EQ,        // >=
+    PLUS,         // +
+    MINUS,        // -
+    STAR,         // *
+    SLASH,        // /
+    PERCENT,      // %
+    BANG,         // !
+    ASSIGN,       // =
+    PIPE,         // |
+    EXACT_EQ,     // ===
+    EXACT_NOT_EQ, // !==
+    AMP_AMP,      // &&
+    PIPE_PIPE,    // ||
+    AMP,          // &
+    CARET,        // ^
+    TILDE,        // ~
+    LT_LT,        // <<
+    /// `>>`, glued by the parser from two adjacent `>` tokens
+    GT_GT,
+    /// `>>>`, glued by the parser from three adjacent `>` tokens
+    GT_GT_GT,
 
     // Delimiters
-    L_PAREN,   // (
-    R_PAREN,   // )
-    L_BRACE,   // {
-    R_BRACE,   // }
-    COMMA,     // ,
-    COLON,     // :
-    SEMICOLON, // ;
+    L_PAREN,      // (
+    R_PAREN,      // )
+    L_BRACE,      // {
+    R_BRACE,      // }
+    L_BRACKET,    // [
+    R_BRACKET,    // ]
+    L_BRACE_PIPE, // {|
+    R_BRACE_PIPE, // |}
+    COMMA,        // ,
+    COLON,        // :
+    DOT,          // .
+    ELLIPSIS,     // ...
+    QUESTION,     // ?
+    SEMICOLON,    // ;
 
     // Composite nodes
     SOURCE_FILE,
     MODULE_PART,
     IMPORT_DECL,
+    IMPORT_ORG_NAME,
+    MODULE_NAME,
+    IMPORT_PREFIX,
     MODULE_DECL,
     FUNCTION_DEF,
+    CONST_DECL,
+    MODULE_VAR_DECL,
+    TYPE_DEFINITION,
     SIGNATURE,
     PARAM_LIST,
+    /// A required parameter, `int x`
     PARAM,
+    /// `int x = 10`
+    DEFAULTABLE_PARAM,
+    /// `int... xs`
+    REST_PARAM,
+    /// `*Options opts`, whose fields may also be passed as named arguments
+    INCLUDED_RECORD_PARAM,
+    /// A basic type name, such as `int` or `anydata`
     TYPE_DESC,
+    NIL_TYPE_DESC,
+    OPTIONAL_TYPE_DESC,
+    UNION_TYPE_DESC,
+    ARRAY_TYPE_DESC,
+    TUPLE_TYPE_DESC,
+    TUPLE_REST_DESC,
+    MAP_TYPE_DESC,
+    RECORD_TYPE_DESC,
+    RECORD_FIELD,
+    RECORD_REST_DESC,
+    TYPE_INCLUSION,
+    SINGLETON_TYPE_DESC,
+    PARENTHESIZED_TYPE_DESC,
+    TYPE_REFERENCE,
     STMT_BLOCK,
     STATEMENT,
     LOCAL_VAR_DECL_STMT,
@@ -80,9 +147,19 @@ pub enum SyntaxKind {
     ADDITIVE_EXPR,
     MULTIPLICATIVE_EXPR,
     UNARY_EXPR,
+    /// `a && b` or `a || b`
+    LOGICAL_EXPR,
+    /// `a & b`, `a ^ b` or `a | b`
+    BITWISE_EXPR,
+    /// `a << b`, `a >> b` or `a >>> b`
+    SHIFT_EXPR,
+    /// `cond ? a : b`
+    CONDITIONAL_EXPR,
     PRIMARY_EXPR,
     FUNCTION_CALL_EXPR,
     ARG_LIST,
+    /// `x = 1` in an argument list
+    NAMED_ARG,
     QUALIFIED_IDENTIFIER,
 
     // Special tokens
@@ -97,37 +174,32 @@ impl TryFrom<u16> for SyntaxKind {
         if value >= (Self::EOF as u16) {
             return Err(());
         }
-        Ok(unsafe { std::mem::transmute(value) })
+        Ok(unsafe { std::mem::transmute::<u16, SyntaxKind>(value) })
     }
 }
 
+pub mod diagnostic;
 pub mod error;
+pub mod explain;
 pub mod lexer;
+pub mod line_index;
+pub mod literal;
 pub mod project;
+pub mod render;
+pub mod source;
+pub mod text_edit;
 pub mod token;
 
+#[cfg(test)]
+mod explain_test;
 #[cfg(test)]
 mod lexer_test;
+#[cfg(test)]
+mod line_index_test;
+#[cfg(test)]
+mod render_test;
 
-// Add this function to convert between token types
+/// Convert a lexer token into the `SyntaxKind` used in the syntax tree
 pub fn convert_token(token: lexer::Token) -> SyntaxKind {
-    match token {
-        lexer::Token::Import => SyntaxKind::IMPORT_KW,
-        lexer::Token::Public => SyntaxKind::PUBLIC_KW,
-        lexer::Token::Function => SyntaxKind::FUNCTION_KW,
-        lexer::Token::Returns => SyntaxKind::RETURNS_KW,
-        lexer::Token::Int => SyntaxKind::INT_KW,
-        lexer::Token::Boolean => SyntaxKind::BOOLEAN_KW,
-        lexer::Token::Identifier => SyntaxKind::IDENTIFIER,
-        lexer::Token::LParen => SyntaxKind::L_PAREN,
-        lexer::Token::RParen => SyntaxKind::R_PAREN,
-        lexer::Token::LBrace => SyntaxKind::L_BRACE,
-        lexer::Token::RBrace => SyntaxKind::R_BRACE,
-        lexer::Token::Comma => SyntaxKind::COMMA,
-        lexer::Token::Semicolon => SyntaxKind::SEMICOLON,
-        lexer::Token::Slash => SyntaxKind::SLASH,
-        lexer::Token::LineComment => SyntaxKind::COMMENT,
-        lexer::Token::Newline => SyntaxKind::WHITESPACE,
-        _ => SyntaxKind::ERROR,
-    }
+    SyntaxKind::from(token)
 }
@@ -0,0 +1,175 @@
+//! Conversion between byte offsets and line/column positions
+//!
+//! Offsets are UTF-8 byte offsets into the source text. Columns come in two
+//! flavors: UTF-8 byte columns, used by the compiler, and UTF-16 code unit
+//! columns, used by the Language Server Protocol. Lines end at `\n`, `\r\n`
+//! or a lone `\r`, and a leading byte order mark takes up no columns.
+
+use rowan::{TextRange, TextSize};
+use std::collections::HashMap;
+
+/// A zero-based line and column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineCol {
+    pub line: u32,
+    pub col: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    /// Offset of the first character of each line
+    line_starts: Vec<TextSize>,
+    /// Offset just past the last character of each line, before its terminator
+    line_ends: Vec<TextSize>,
+    /// Characters that take more than one UTF-8 byte, per line
+    wide_chars: HashMap<u32, Vec<WideChar>>,
+    /// Length of a leading byte order mark, if any
+    bom_len: TextSize,
+}
+
+/// A non-ASCII character, with its offset relative to the start of its line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WideChar {
+    start: TextSize,
+    len_utf8: TextSize,
+    len_utf16: TextSize,
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let bom_len = if text.starts_with('\u{FEFF}') {
+            TextSize::of('\u{FEFF}')
+        } else {
+            0.into()
+        };
+
+        let mut line_starts = vec![bom_len];
+        let mut line_ends = Vec::new();
+        let mut wide_chars = HashMap::new();
+        let mut line_start = bom_len;
+
+        let mut chars = text[usize::from(bom_len)..].char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            let offset = TextSize::try_from(i).unwrap() + bom_len;
+            match c {
+                '\n' | '\r' => {
+                    let mut terminator = TextSize::of(c);
+                    if c == '\r' && matches!(chars.peek(), Some((_, '\n'))) {
+                        chars.next();
+                        terminator += TextSize::of('\n');
+                    }
+                    line_ends.push(offset);
+                    line_start = offset + terminator;
+                    line_starts.push(line_start);
+                }
+                c if !c.is_ascii() => {
+                    let line = (line_starts.len() - 1) as u32;
+                    wide_chars
+                        .entry(line)
+                        .or_insert_with(Vec::new)
+                        .push(WideChar {
+                            start: offset - line_start,
+                            len_utf8: TextSize::of(c),
+                            len_utf16: TextSize::from(c.len_utf16() as u32),
+                        });
+                }
+                _ => {}
+            }
+        }
+        line_ends.push(TextSize::of(text));
+
+        Self {
+            line_starts,
+            line_ends,
+            wide_chars,
+            bom_len,
+        }
+    }
+
+    pub fn line_count(&self) -> u32 {
+        self.line_starts.len() as u32
+    }
+
+    /// The line and UTF-8 column of `offset`
+    pub fn line_col(&self, offset: TextSize) -> LineCol {
+        let offset = offset.max(self.bom_len);
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        LineCol {
+            line: line as u32,
+            col: (offset - self.line_starts[line]).into(),
+        }
+    }
+
+    /// The offset of a line and UTF-8 column, if it lies within the text and
+    /// not inside a character
+    pub fn offset(&self, line_col: LineCol) -> Option<TextSize> {
+        let line = line_col.line as usize;
+        let start = *self.line_starts.get(line)?;
+        let col = TextSize::from(line_col.col);
+        if self
+            .wide_chars_on(line_col.line)
+            .any(|c| c.start < col && col < c.start + c.len_utf8)
+        {
+            return None;
+        }
+        let offset = start + col;
+        (offset <= self.line_ends[line]).then_some(offset)
+    }
+
+    /// The line and UTF-16 column of `offset`
+    pub fn line_col_utf16(&self, offset: TextSize) -> LineCol {
+        self.to_utf16(self.line_col(offset))
+    }
+
+    /// The offset of a line and UTF-16 column, if it lies within the text
+    pub fn offset_utf16(&self, line_col: LineCol) -> Option<TextSize> {
+        self.offset(self.to_utf8(line_col))
+    }
+
+    /// Convert a UTF-8 column into a UTF-16 column on the same line
+    pub fn to_utf16(&self, line_col: LineCol) -> LineCol {
+        let mut col = line_col.col;
+        for c in self.wide_chars_on(line_col.line) {
+            if u32::from(c.start) >= line_col.col {
+                break;
+            }
+            col -= u32::from(c.len_utf8) - u32::from(c.len_utf16);
+        }
+        LineCol { col, ..line_col }
+    }
+
+    /// Convert a UTF-16 column into a UTF-8 column on the same line. A column
+    /// between the two halves of a surrogate pair maps to the start of its
+    /// character.
+    pub fn to_utf8(&self, line_col: LineCol) -> LineCol {
+        let mut col = line_col.col;
+        for c in self.wide_chars_on(line_col.line) {
+            // `c.start` is a UTF-8 column; compare in UTF-16 units
+            let start_utf16 = u32::from(c.start) - (col - line_col.col);
+            if start_utf16 >= line_col.col {
+                break;
+            }
+            if start_utf16 + u32::from(c.len_utf16) > line_col.col {
+                return LineCol {
+                    col: c.start.into(),
+                    ..line_col
+                };
+            }
+            col += u32::from(c.len_utf8) - u32::from(c.len_utf16);
+        }
+        LineCol { col, ..line_col }
+    }
+
+    /// The range of a line's text, without its line terminator
+    pub fn line_range(&self, line: u32) -> Option<TextRange> {
+        let line = line as usize;
+        Some(TextRange::new(
+            *self.line_starts.get(line)?,
+            self.line_ends[line],
+        ))
+    }
+
+    fn wide_chars_on(&self, line: u32) -> impl Iterator<Item = &WideChar> {
+        self.wide_chars.get(&line).into_iter().flatten()
+    }
+}
@@ -0,0 +1,85 @@
+#[cfg(test)]
+mod tests {
+    use crate::line_index::{LineCol, LineIndex};
+    use rowan::{TextRange, TextSize};
+
+    fn lc(line: u32, col: u32) -> LineCol {
+        LineCol { line, col }
+    }
+
+    #[test]
+    fn test_line_col_round_trip() {
+        let text = "int a;\nint b;\n\nend";
+        let index = LineIndex::new(text);
+        assert_eq!(index.line_count(), 4);
+
+        for offset in 0..=text.len() as u32 {
+            let offset = TextSize::from(offset);
+            let line_col = index.line_col(offset);
+            assert_eq!(index.offset(line_col), Some(offset));
+        }
+        assert_eq!(index.line_col(7.into()), lc(1, 0));
+        assert_eq!(index.line_col(15.into()), lc(3, 0));
+        assert_eq!(index.offset(lc(0, 7)), None);
+        assert_eq!(index.offset(lc(4, 0)), None);
+    }
+
+    #[test]
+    fn test_crlf_line_endings() {
+        let text = "a\r\nbc\rd\n";
+        let index = LineIndex::new(text);
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line_col(3.into()), lc(1, 0));
+        assert_eq!(index.line_col(6.into()), lc(2, 0));
+        assert_eq!(
+            index.line_range(0),
+            Some(TextRange::new(0.into(), 1.into()))
+        );
+        assert_eq!(
+            index.line_range(1),
+            Some(TextRange::new(3.into(), 5.into()))
+        );
+        assert_eq!(&text[index.line_range(2).unwrap()], "d");
+    }
+
+    #[test]
+    fn test_byte_order_mark() {
+        let text = "\u{FEFF}int x;\nint y;";
+        let index = LineIndex::new(text);
+        let bom = TextSize::of('\u{FEFF}');
+
+        assert_eq!(index.line_col(bom), lc(0, 0));
+        assert_eq!(index.line_col(bom + TextSize::from(4)), lc(0, 4));
+        assert_eq!(index.offset(lc(0, 0)), Some(bom));
+        assert_eq!(&text[index.line_range(0).unwrap()], "int x;");
+        assert_eq!(index.line_col_utf16(bom + TextSize::from(4)), lc(0, 4));
+    }
+
+    #[test]
+    fn test_utf16_columns() {
+        // 'é' is 2 UTF-8 bytes and 1 UTF-16 unit; '😀' is 4 and 2
+        let text = "x\né😀 = y;";
+        let index = LineIndex::new(text);
+        let y = TextSize::try_from(text.find('y').unwrap()).unwrap();
+
+        assert_eq!(index.line_col(y), lc(1, 9));
+        assert_eq!(index.line_col_utf16(y), lc(1, 6));
+        assert_eq!(index.offset_utf16(lc(1, 6)), Some(y));
+        assert_eq!(index.to_utf16(lc(1, 2)), lc(1, 1));
+        assert_eq!(index.to_utf8(lc(1, 1)), lc(1, 2));
+        assert_eq!(index.to_utf8(lc(1, 3)), lc(1, 6));
+        assert_eq!(index.to_utf16(lc(0, 1)), lc(0, 1));
+    }
+
+    #[test]
+    fn test_columns_inside_a_character() {
+        let index = LineIndex::new("😀x\n");
+
+        assert_eq!(index.offset(lc(0, 2)), None);
+        assert_eq!(index.offset(lc(0, 4)), Some(TextSize::from(4)));
+        // Between the halves of the surrogate pair
+        assert_eq!(index.to_utf8(lc(0, 1)), lc(0, 0));
+        assert_eq!(index.offset_utf16(lc(0, 1)), Some(TextSize::from(0)));
+        assert_eq!(index.offset_utf16(lc(0, 2)), Some(TextSize::from(4)));
+    }
+}
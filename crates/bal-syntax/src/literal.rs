@@ -0,0 +1,228 @@
+//! Decoding and validation of literal and identifier token text
+
+use std::ops::Range;
+
+/// The value of an int or floating point literal token
+#[derive(Debug, Clone, PartialEq)]
+pub enum NumericValue {
+    Int(i64),
+    Float(f64),
+    /// Decimal literals keep their digits; the value is exact and not an `f64`
+    Decimal(String),
+}
+
+/// An invalid escape sequence inside a string literal or identifier
+#[derive(Debug, Clone, PartialEq)]
+pub struct EscapeError {
+    /// Byte range of the escape sequence, relative to the start of the token
+    pub range: Range<usize>,
+    pub message: String,
+}
+
+/// Decode the value of a string literal token, including its quotes.
+///
+/// Supports the Ballerina single-character escapes (`\t`, `\n`, `\r`, `\\`,
+/// `\"`) and numeric escapes of the form `\u{XXXX}`. All invalid escapes are
+/// reported, not just the first one.
+pub fn string_value(text: &str) -> Result<String, Vec<EscapeError>> {
+    let (base, body) = match text.strip_prefix('"') {
+        Some(rest) => (1, rest.strip_suffix('"').unwrap_or(rest)),
+        None => (0, text),
+    };
+
+    let mut value = String::with_capacity(body.len());
+    let mut errors = Vec::new();
+    let mut chars = body.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+
+        let escaped = match chars.next() {
+            Some((_, escaped)) => escaped,
+            None => {
+                errors.push(EscapeError {
+                    range: base + start..base + start + 1,
+                    message: "incomplete escape sequence".to_string(),
+                });
+                break;
+            }
+        };
+
+        match escaped {
+            't' => value.push('\t'),
+            'n' => value.push('\n'),
+            'r' => value.push('\r'),
+            '\\' => value.push('\\'),
+            '"' => value.push('"'),
+            'u' => {
+                let (end, code_point) = numeric_escape(body, start, &mut chars);
+                match code_point {
+                    Ok(c) => value.push(c),
+                    Err(message) => errors.push(EscapeError {
+                        range: base + start..base + end,
+                        message,
+                    }),
+                }
+            }
+            other => errors.push(EscapeError {
+                range: base + start..base + start + 1 + other.len_utf8(),
+                message: format!("unknown escape sequence '\\{}'", other),
+            }),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(value)
+    } else {
+        Err(errors)
+    }
+}
+
+/// The name an identifier token stands for.
+///
+/// The `'` of a quoted identifier is dropped and `\X` and `\u{XXXX}` escapes
+/// are decoded, so `'int`, `a\-b` and `\u{61}` name `int`, `a-b` and `a`.
+pub fn identifier_value(text: &str) -> Result<String, Vec<EscapeError>> {
+    let (base, body) = match text.strip_prefix('\'') {
+        Some(rest) => (1, rest),
+        None => (0, text),
+    };
+    if !body.contains('\\') {
+        return Ok(body.to_string());
+    }
+
+    let mut value = String::with_capacity(body.len());
+    let mut errors = Vec::new();
+    let mut chars = body.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some((_, 'u')) => {
+                let (end, code_point) = numeric_escape(body, start, &mut chars);
+                match code_point {
+                    Ok(c) => value.push(c),
+                    Err(message) => errors.push(EscapeError {
+                        range: base + start..base + end,
+                        message,
+                    }),
+                }
+            }
+            Some((_, escaped)) => value.push(escaped),
+            None => errors.push(EscapeError {
+                range: base + start..base + start + 1,
+                message: "incomplete escape sequence".to_string(),
+            }),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(value)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parse the `{XXXX}` part of a `\u{XXXX}` escape that starts at `start`.
+/// Returns the end offset of the escape and the decoded character.
+fn numeric_escape(
+    body: &str,
+    start: usize,
+    chars: &mut std::iter::Peekable<std::str::CharIndices<'_>>,
+) -> (usize, Result<char, String>) {
+    let mut end = start + 2;
+    if !matches!(chars.peek(), Some((_, '{'))) {
+        return (end, Err("expected '{' after '\\u'".to_string()));
+    }
+    chars.next();
+    end += 1;
+
+    let digits_start = end;
+    while let Some(&(i, c)) = chars.peek() {
+        if c == '}' {
+            chars.next();
+            let digits = &body[digits_start..i];
+            end = i + 1;
+            return (end, decode_code_point(digits));
+        }
+        if !c.is_ascii_hexdigit() {
+            break;
+        }
+        chars.next();
+        end = i + c.len_utf8();
+    }
+
+    (
+        end,
+        Err("unterminated unicode escape, expected '}'".to_string()),
+    )
+}
+
+fn decode_code_point(digits: &str) -> Result<char, String> {
+    if digits.is_empty() || digits.len() > 6 {
+        return Err("unicode escape must have between 1 and 6 hex digits".to_string());
+    }
+    let value = u32::from_str_radix(digits, 16).map_err(|e| e.to_string())?;
+    char::from_u32(value).ok_or_else(|| format!("'{:X}' is not a valid unicode code point", value))
+}
+
+/// Whether `text` is the int literal `9223372036854775808` (or its hex
+/// form), which is only in range as the operand of `-`, to write the int
+/// minimum
+pub fn is_int_min_magnitude(text: &str) -> bool {
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => i64::from_str_radix(&format!("-{}", hex), 16) == Ok(i64::MIN),
+        None => format!("-{}", text).parse::<i64>() == Ok(i64::MIN),
+    }
+}
+
+/// Decode an int literal (decimal or `0x` hex) or a floating point literal.
+///
+/// Int literals must fit in Ballerina's signed 64-bit `int`. Floating point
+/// literals with a `d`/`D` suffix are decimals; all others are floats.
+pub fn numeric_value(text: &str) -> Result<NumericValue, String> {
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        return i64::from_str_radix(hex, 16)
+            .map(NumericValue::Int)
+            .map_err(|_| format!("int literal '{}' is out of range for int", text));
+    }
+
+    if text.bytes().all(|b| b.is_ascii_digit()) {
+        return text
+            .parse::<i64>()
+            .map(NumericValue::Int)
+            .map_err(|_| format!("int literal '{}' is out of range for int", text));
+    }
+
+    match text.as_bytes()[text.len() - 1] {
+        b'd' | b'D' => {
+            let digits = &text[..text.len() - 1];
+            // Validate the shape; the digits themselves are kept exactly
+            digits
+                .parse::<f64>()
+                .map(|_| NumericValue::Decimal(digits.to_string()))
+                .map_err(|e| format!("invalid decimal literal '{}': {}", text, e))
+        }
+        suffix => {
+            let digits = if matches!(suffix, b'f' | b'F') {
+                &text[..text.len() - 1]
+            } else {
+                text
+            };
+            match digits.parse::<f64>() {
+                Ok(value) if value.is_finite() => Ok(NumericValue::Float(value)),
+                Ok(_) => Err(format!(
+                    "float literal '{}' is out of range for float",
+                    text
+                )),
+                Err(e) => Err(format!("invalid float literal '{}': {}", text, e)),
+            }
+        }
+    }
+}
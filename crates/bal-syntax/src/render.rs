@@ -0,0 +1,304 @@
+//! Terminal rendering of diagnostics
+//!
+//! Source lines come from the `SourceDb`, so rendering never touches the
+//! disk. Labels spanning several lines are drawn with a margin line joining
+//! their start and end, secondary labels are underlined with `-`, and ANSI
+//! color is only used when asked for.
+
+use crate::diagnostic::{Diagnostic, Diagnostics, Label, Severity};
+use crate::line_index::LineCol;
+use crate::source::{FileId, SourceDb, SourceFile};
+use std::fmt::{self, Write};
+
+/// Lines of a multi-line label shown before and after the elided middle
+const CONTEXT_LINES: u32 = 2;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Renderer {
+    color: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Style {
+    Severity(Severity),
+    Secondary,
+    Gutter,
+    Bold,
+}
+
+/// A label resolved to display lines and columns
+struct Mark<'a> {
+    label: &'a Label,
+    primary: bool,
+    start: LineCol,
+    end: LineCol,
+}
+
+impl Mark<'_> {
+    fn is_multiline(&self) -> bool {
+        self.end.line > self.start.line
+    }
+}
+
+impl Renderer {
+    pub fn new(color: bool) -> Self {
+        Self { color }
+    }
+
+    pub fn render(&self, db: &SourceDb, diagnostic: &Diagnostic) -> String {
+        let mut out = String::new();
+        self.write(&mut out, db, diagnostic)
+            .expect("writing to a String cannot fail");
+        out
+    }
+
+    pub fn write(
+        &self,
+        out: &mut impl Write,
+        db: &SourceDb,
+        diagnostic: &Diagnostic,
+    ) -> fmt::Result {
+        let severity = Style::Severity(diagnostic.severity);
+        writeln!(
+            out,
+            "{}{}",
+            self.paint(
+                severity,
+                &format!("{}[{}]", diagnostic.severity, diagnostic.code)
+            ),
+            self.paint(Style::Bold, &format!(": {}", diagnostic.message))
+        )?;
+
+        let marks: Vec<_> = diagnostic
+            .primary
+            .iter()
+            .map(|label| (label, true))
+            .chain(diagnostic.labels.iter().map(|label| (label, false)))
+            .map(|(label, primary)| {
+                let file = db.file(label.span.file);
+                Mark {
+                    label,
+                    primary,
+                    start: display_col(file, label.span.range.start()),
+                    end: last_char(file, label),
+                }
+            })
+            .collect();
+        let width = marks
+            .iter()
+            .map(|mark| (mark.end.line + 1).to_string().len())
+            .max()
+            .unwrap_or(1);
+        let pad = " ".repeat(width);
+
+        // One section per file, the primary label's file first
+        let mut files: Vec<FileId> = Vec::new();
+        for mark in &marks {
+            if !files.contains(&mark.label.span.file) {
+                files.push(mark.label.span.file);
+            }
+        }
+        for (i, &id) in files.iter().enumerate() {
+            let file = db.file(id);
+            let mut section: Vec<_> = marks.iter().filter(|m| m.label.span.file == id).collect();
+            let first = section[0];
+            writeln!(
+                out,
+                "{}{} {}:{}:{}",
+                pad,
+                self.paint(Style::Gutter, if i == 0 { "-->" } else { ":::" }),
+                file.path,
+                first.start.line + 1,
+                first.start.col + 1
+            )?;
+            writeln!(out, "{} {}", pad, self.paint(Style::Gutter, "|"))?;
+            section.sort_by_key(|mark| (mark.start, !mark.primary));
+            self.write_section(out, file, &section, diagnostic.severity, width)?;
+        }
+
+        if !diagnostic.notes.is_empty() || diagnostic.help.is_some() || !diagnostic.fixes.is_empty()
+        {
+            writeln!(out, "{} {}", pad, self.paint(Style::Gutter, "|"))?;
+        }
+        for note in &diagnostic.notes {
+            writeln!(
+                out,
+                "{} {} note: {}",
+                pad,
+                self.paint(Style::Gutter, "="),
+                note
+            )?;
+        }
+        if let Some(help) = &diagnostic.help {
+            writeln!(
+                out,
+                "{} {} help: {}",
+                pad,
+                self.paint(Style::Gutter, "="),
+                help
+            )?;
+        }
+        for fix in &diagnostic.fixes {
+            writeln!(
+                out,
+                "{} {} help: {}",
+                pad,
+                self.paint(Style::Gutter, "="),
+                fix.message
+            )?;
+        }
+        Ok(())
+    }
+
+    fn write_section(
+        &self,
+        out: &mut impl Write,
+        file: &SourceFile,
+        marks: &[&Mark],
+        severity: Severity,
+        width: usize,
+    ) -> fmt::Result {
+        let margin = marks.iter().any(|mark| mark.is_multiline());
+        let mut last_line: Option<u32> = None;
+
+        for mark in marks {
+            let style = match mark.primary {
+                true => Style::Severity(severity),
+                false => Style::Secondary,
+            };
+            let marker = if mark.primary { "^" } else { "-" };
+            let (start, end) = (mark.start, mark.end);
+
+            let mut lines = vec![start.line];
+            if mark.is_multiline() {
+                if end.line - start.line > 2 * CONTEXT_LINES {
+                    lines.extend(start.line + 1..start.line + CONTEXT_LINES);
+                    lines.extend(end.line + 1 - CONTEXT_LINES..=end.line);
+                } else {
+                    lines.extend(start.line + 1..=end.line);
+                }
+            }
+
+            for line in lines {
+                // Labels on a line already shown only add their underline
+                if last_line.is_none_or(|last| line > last) {
+                    if last_line.is_some_and(|last| line > last + 1) {
+                        writeln!(out, "{}", self.paint(Style::Gutter, "..."))?;
+                    }
+                    let lane = if margin && line > start.line && line <= end.line {
+                        self.paint(style, "| ")
+                    } else if margin {
+                        "  ".to_string()
+                    } else {
+                        String::new()
+                    };
+                    writeln!(
+                        out,
+                        "{} {}{}",
+                        self.paint(Style::Gutter, &format!("{:>width$} |", line + 1)),
+                        lane,
+                        line_text(file, line).trim_end()
+                    )?;
+                    last_line = Some(line);
+                }
+
+                if !mark.is_multiline() {
+                    let underline = marker.repeat((end.col + 1 - start.col) as usize);
+                    let indent = format!(
+                        "{}{}",
+                        if margin { "  " } else { "" },
+                        " ".repeat(start.col as usize)
+                    );
+                    let underline = format!("{}{}", indent, self.paint(style, &underline));
+                    self.write_marker(out, width, &underline, &mark.label.message, style)?;
+                } else if line == start.line {
+                    let underline = format!(" {}{}", "_".repeat(start.col as usize + 1), marker);
+                    self.write_marker(out, width, &self.paint(style, &underline), "", style)?;
+                } else if line == end.line {
+                    let underline = format!("|{}{}", "_".repeat(end.col as usize + 1), marker);
+                    let underline = self.paint(style, &underline);
+                    self.write_marker(out, width, &underline, &mark.label.message, style)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn write_marker(
+        &self,
+        out: &mut impl Write,
+        width: usize,
+        underline: &str,
+        message: &str,
+        style: Style,
+    ) -> fmt::Result {
+        write!(
+            out,
+            "{} {} {}",
+            " ".repeat(width),
+            self.paint(Style::Gutter, "|"),
+            underline
+        )?;
+        if message.is_empty() {
+            writeln!(out)
+        } else {
+            writeln!(out, " {}", self.paint(style, message))
+        }
+    }
+
+    fn paint(&self, style: Style, text: &str) -> String {
+        if !self.color {
+            return text.to_string();
+        }
+        let code = match style {
+            Style::Severity(Severity::Error) => "1;31",
+            Style::Severity(Severity::Warning) => "1;33",
+            Style::Severity(Severity::Hint) => "1;36",
+            Style::Secondary | Style::Gutter => "1;34",
+            Style::Bold => "1",
+        };
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    }
+}
+
+/// The closing line of a build, such as `2 errors, 1 warning`
+pub fn summary(diagnostics: &Diagnostics) -> String {
+    let count = |n: usize, what: &str| format!("{} {}{}", n, what, if n == 1 { "" } else { "s" });
+    format!(
+        "{}, {}",
+        count(diagnostics.error_count(), "error"),
+        count(diagnostics.warning_count(), "warning")
+    )
+}
+
+fn line_text(file: &SourceFile, line: u32) -> &str {
+    file.line_index
+        .line_range(line)
+        .map(|range| &file.text[range])
+        .unwrap_or_default()
+}
+
+/// The line and column, counted in characters, of `offset`
+fn display_col(file: &SourceFile, offset: rowan::TextSize) -> LineCol {
+    let line_col = file.line_index.line_col(offset);
+    let line_start = usize::from(offset) - line_col.col as usize;
+    LineCol {
+        col: file.text[line_start..offset.into()].chars().count() as u32,
+        ..line_col
+    }
+}
+
+/// The position of the last character in a label, or of its start if empty.
+/// A range ending with a line break ends on the line before it.
+fn last_char(file: &SourceFile, label: &Label) -> LineCol {
+    let range = label.span.range;
+    if range.is_empty() {
+        return display_col(file, range.start());
+    }
+    let text = &file.text[range];
+    let last = text.trim_end_matches(['\n', '\r']);
+    let last = if last.is_empty() { text } else { last };
+    let offset = range.start() + rowan::TextSize::of(last)
+        - rowan::TextSize::of(last.chars().last().unwrap_or(' '));
+    display_col(file, offset)
+}
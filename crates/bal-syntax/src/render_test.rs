@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use crate::diagnostic::{codes, Diagnostic, Diagnostics};
+    use crate::error::Span;
+    use crate::render::{summary, Renderer};
+    use crate::source::{FileId, SourceDb};
+    use rowan::{TextRange, TextSize};
+
+    fn span(db: &SourceDb, file: FileId, text: &str) -> Span {
+        let start = db
+            .file(file)
+            .text
+            .find(text)
+            .expect("text should be in file");
+        let start = TextSize::try_from(start).unwrap();
+        Span::new(file, TextRange::at(start, TextSize::of(text)))
+    }
+
+    #[test]
+    fn test_single_line() {
+        let mut db = SourceDb::new();
+        let file = db.add_file("main.bal", "function f() {\n    int x = 1\n}\n");
+        let diagnostic = Diagnostic::error(codes::UNEXPECTED_TOKEN, "unexpected token")
+            .with_primary(span(&db, file, "1"), "expected ';', found '}'")
+            .with_help("add ';' here");
+
+        assert_eq!(
+            Renderer::new(false).render(&db, &diagnostic),
+            "\
+error[BCE2001]: unexpected token
+ --> main.bal:2:13
+  |
+2 |     int x = 1
+  |             ^ expected ';', found '}'
+  |
+  = help: add ';' here
+"
+        );
+    }
+
+    #[test]
+    fn test_multi_line_span() {
+        let mut db = SourceDb::new();
+        let file = db.add_file("main.bal", "function f() {\n    int x = 1;\n}\n");
+        let block = span(&db, file, "{\n    int x = 1;\n}");
+        let diagnostic = Diagnostic::warning("BCE9999", "block does nothing useful")
+            .with_primary(block, "this block");
+
+        assert_eq!(
+            Renderer::new(false).render(&db, &diagnostic),
+            "\
+warning[BCE9999]: block does nothing useful
+ --> main.bal:1:14
+  |
+1 |   function f() {
+  |  ______________^
+2 | |     int x = 1;
+3 | | }
+  | |_^ this block
+"
+        );
+    }
+
+    #[test]
+    fn test_long_span_is_elided() {
+        let mut db = SourceDb::new();
+        let text: String = (1..=12).map(|i| format!("line {}\n", i)).collect();
+        let file = db.add_file("long.bal", text);
+        let range = span(
+            &db,
+            file,
+            "line 2\nline 3\nline 4\nline 5\nline 6\nline 7\nline 8\nline 9\nline 10",
+        );
+        let rendered = Renderer::new(false).render(
+            &db,
+            &Diagnostic::error("BCE9999", "long").with_primary(range, "here"),
+        );
+
+        assert!(rendered.contains(" 2 |   line 2\n"));
+        assert!(rendered.contains(" 3 | | line 3\n...\n 9 | | line 9\n10 | | line 10\n"));
+        assert!(!rendered.contains("line 5"));
+        assert!(rendered.ends_with("   | |_______^ here\n"));
+    }
+
+    #[test]
+    fn test_secondary_labels() {
+        let mut db = SourceDb::new();
+        let main = db.add_file("main.bal", "function f() { }\n\nfunction f() { }\n");
+        let other = db.add_file("other.bal", "function g() { }\n");
+        let diagnostic = Diagnostic::error(codes::DUPLICATE_DEFINITION, "duplicate definition")
+            .with_primary(
+                Span::new(main, TextRange::new(27.into(), 28.into())),
+                "redefined here",
+            )
+            .with_label(
+                Span::new(main, TextRange::new(9.into(), 10.into())),
+                "first defined here",
+            )
+            .with_label(span(&db, other, "g"), "also related")
+            .with_note("functions share one namespace");
+
+        assert_eq!(
+            Renderer::new(false).render(&db, &diagnostic),
+            "\
+error[BCE3001]: duplicate definition
+ --> main.bal:3:10
+  |
+1 | function f() { }
+  |          - first defined here
+...
+3 | function f() { }
+  |          ^ redefined here
+ ::: other.bal:1:10
+  |
+1 | function g() { }
+  |          - also related
+  |
+  = note: functions share one namespace
+"
+        );
+    }
+
+    #[test]
+    fn test_columns_count_characters() {
+        let mut db = SourceDb::new();
+        let file = db.add_file("main.bal", "string s = \"é\" $;\n");
+        let diagnostic = Diagnostic::error(codes::INVALID_CHARACTER, "invalid character")
+            .with_primary(span(&db, file, "$"), "here");
+        let rendered = Renderer::new(false).render(&db, &diagnostic);
+        // 'é' is two bytes but one column
+        let caret = format!("\n  | {}^ here\n", " ".repeat(15));
+        assert!(rendered.contains(&caret), "{}", rendered);
+        assert!(rendered.contains(" --> main.bal:1:16\n"));
+    }
+
+    #[test]
+    fn test_color() {
+        let mut db = SourceDb::new();
+        let file = db.add_file("main.bal", "$");
+        let diagnostic = Diagnostic::error(codes::INVALID_CHARACTER, "invalid character")
+            .with_primary(span(&db, file, "$"), "here");
+
+        let colored = Renderer::new(true).render(&db, &diagnostic);
+        assert!(colored.starts_with("\x1b[1;31merror[BCE1001]\x1b[0m"));
+        assert!(!Renderer::new(false)
+            .render(&db, &diagnostic)
+            .contains('\x1b'));
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut diagnostics = Diagnostics::new();
+        assert_eq!(summary(&diagnostics), "0 errors, 0 warnings");
+        diagnostics.push(Diagnostic::error("BCE9999", "a"));
+        diagnostics.push(Diagnostic::warning("BCE9999", "b"));
+        diagnostics.push(Diagnostic::warning("BCE9999", "c"));
+        diagnostics.push(Diagnostic::hint("BCE9999", "d"));
+        assert_eq!(summary(&diagnostics), "1 error, 2 warnings");
+    }
+}
@@ -0,0 +1,62 @@
+//! Edits to source text, as sent by editors and produced by fixes
+
+use rowan::{TextRange, TextSize};
+
+/// Replace the text in `delete` with `insert`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextEdit {
+    pub delete: TextRange,
+    pub insert: String,
+}
+
+impl TextEdit {
+    pub fn replace(delete: TextRange, insert: impl Into<String>) -> Self {
+        Self {
+            delete,
+            insert: insert.into(),
+        }
+    }
+
+    pub fn insert(offset: TextSize, text: impl Into<String>) -> Self {
+        Self::replace(TextRange::empty(offset), text)
+    }
+
+    pub fn delete(range: TextRange) -> Self {
+        Self::replace(range, String::new())
+    }
+
+    /// The text after applying this edit to `text`
+    pub fn apply(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len() + self.insert.len());
+        result.push_str(&text[..self.delete.start().into()]);
+        result.push_str(&self.insert);
+        result.push_str(&text[self.delete.end().into()..]);
+        result
+    }
+}
+
+/// Apply several edits to `text`. The edits are against the original text;
+/// an edit overlapping one that comes before it is skipped.
+/// Returns the new text and how many edits were applied.
+pub fn apply_edits<'a>(
+    text: &str,
+    edits: impl IntoIterator<Item = &'a TextEdit>,
+) -> (String, usize) {
+    let mut edits: Vec<_> = edits.into_iter().collect();
+    edits.sort_by_key(|edit| (edit.delete.start(), edit.delete.end()));
+
+    let mut result = String::with_capacity(text.len());
+    let mut end = TextSize::from(0);
+    let mut applied = 0;
+    for edit in edits {
+        if edit.delete.start() < end {
+            continue;
+        }
+        result.push_str(&text[TextRange::new(end, edit.delete.start())]);
+        result.push_str(&edit.insert);
+        end = edit.delete.end();
+        applied += 1;
+    }
+    result.push_str(&text[usize::from(end)..]);
+    (result, applied)
+}
@@ -1,5 +1,7 @@
+use crate::lexer;
 use crate::{BallerinaLanguage, SyntaxKind};
-use rowan::Language;
+use rowan::{Language, TextRange};
+use std::fmt;
 
 impl Language for BallerinaLanguage {
     type Kind = SyntaxKind;
@@ -13,8 +15,278 @@ impl Language for BallerinaLanguage {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// A token in a lexed file: its kind and where its text is in the source.
+/// Tokens do not own their text; slice it out of the source when needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Token {
     pub kind: SyntaxKind,
-    pub text: &'static str,
+    pub range: TextRange,
+}
+
+impl Token {
+    pub fn new(kind: SyntaxKind, range: TextRange) -> Self {
+        Self { kind, range }
+    }
+
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.range]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenClass {
+    Keyword,
+    Operator,
+    Delimiter,
+}
+
+/// Every token with a fixed spelling, together with its class.
+/// This is the single place that defines the canonical text of a kind.
+const FIXED_TOKENS: &[(SyntaxKind, &str, TokenClass)] = &[
+    // Keywords
+    (SyntaxKind::IMPORT_KW, "import", TokenClass::Keyword),
+    (SyntaxKind::PUBLIC_KW, "public", TokenClass::Keyword),
+    (SyntaxKind::FUNCTION_KW, "function", TokenClass::Keyword),
+    (SyntaxKind::RETURNS_KW, "returns", TokenClass::Keyword),
+    (SyntaxKind::INT_KW, "int", TokenClass::Keyword),
+    (SyntaxKind::BOOLEAN_KW, "boolean", TokenClass::Keyword),
+    (SyntaxKind::FLOAT_KW, "float", TokenClass::Keyword),
+    (SyntaxKind::DECIMAL_KW, "decimal", TokenClass::Keyword),
+    (SyntaxKind::IF_KW, "if", TokenClass::Keyword),
+    (SyntaxKind::ELSE_KW, "else", TokenClass::Keyword),
+    (SyntaxKind::WHILE_KW, "while", TokenClass::Keyword),
+    (SyntaxKind::BREAK_KW, "break", TokenClass::Keyword),
+    (SyntaxKind::CONTINUE_KW, "continue", TokenClass::Keyword),
+    (SyntaxKind::RETURN_KW, "return", TokenClass::Keyword),
+    (SyntaxKind::TRUE_KW, "true", TokenClass::Keyword),
+    (SyntaxKind::FALSE_KW, "false", TokenClass::Keyword),
+    (SyntaxKind::AS_KW, "as", TokenClass::Keyword),
+    (SyntaxKind::CONST_KW, "const", TokenClass::Keyword),
+    (SyntaxKind::FINAL_KW, "final", TokenClass::Keyword),
+    (
+        SyntaxKind::CONFIGURABLE_KW,
+        "configurable",
+        TokenClass::Keyword,
+    ),
+    (SyntaxKind::TYPE_KW, "type", TokenClass::Keyword),
+    (SyntaxKind::STRING_KW, "string", TokenClass::Keyword),
+    (SyntaxKind::ANY_KW, "any", TokenClass::Keyword),
+    (SyntaxKind::ANYDATA_KW, "anydata", TokenClass::Keyword),
+    (SyntaxKind::JSON_KW, "json", TokenClass::Keyword),
+    (SyntaxKind::NEVER_KW, "never", TokenClass::Keyword),
+    (SyntaxKind::READONLY_KW, "readonly", TokenClass::Keyword),
+    (SyntaxKind::MAP_KW, "map", TokenClass::Keyword),
+    (SyntaxKind::RECORD_KW, "record", TokenClass::Keyword),
+    // Operators
+    (SyntaxKind::EQ, "==", TokenClass::Operator),
+    (SyntaxKind::NOT_EQ, "!=", TokenClass::Operator),
+    (SyntaxKind::LT, "<", TokenClass::Operator),
+    (SyntaxKind::LT_EQ, "<=", TokenClass::Operator),
+    (SyntaxKind::GT, ">", TokenClass::Operator),
+    (SyntaxKind::GT_EQ, ">=", TokenClass::Operator),
+    (SyntaxKind::PLUS, "+", TokenClass::Operator),
+    (SyntaxKind::MINUS, "-", TokenClass::Operator),
+    (SyntaxKind::STAR, "*", TokenClass::Operator),
+    (SyntaxKind::SLASH, "/", TokenClass::Operator),
+    (SyntaxKind::PERCENT, "%", TokenClass::Operator),
+    (SyntaxKind::BANG, "!", TokenClass::Operator),
+    (SyntaxKind::ASSIGN, "=", TokenClass::Operator),
+    (SyntaxKind::PIPE, "|", TokenClass::Operator),
+    (SyntaxKind::EXACT_EQ, "===", TokenClass::Operator),
+    (SyntaxKind::EXACT_NOT_EQ, "!==", TokenClass::Operator),
+    (SyntaxKind::AMP_AMP, "&&", TokenClass::Operator),
+    (SyntaxKind::PIPE_PIPE, "||", TokenClass::Operator),
+    (SyntaxKind::AMP, "&", TokenClass::Operator),
+    (SyntaxKind::CARET, "^", TokenClass::Operator),
+    (SyntaxKind::TILDE, "~", TokenClass::Operator),
+    (SyntaxKind::LT_LT, "<<", TokenClass::Operator),
+    (SyntaxKind::GT_GT, ">>", TokenClass::Operator),
+    (SyntaxKind::GT_GT_GT, ">>>", TokenClass::Operator),
+    // Delimiters
+    (SyntaxKind::L_PAREN, "(", TokenClass::Delimiter),
+    (SyntaxKind::R_PAREN, ")", TokenClass::Delimiter),
+    (SyntaxKind::L_BRACE, "{", TokenClass::Delimiter),
+    (SyntaxKind::R_BRACE, "}", TokenClass::Delimiter),
+    (SyntaxKind::L_BRACKET, "[", TokenClass::Delimiter),
+    (SyntaxKind::R_BRACKET, "]", TokenClass::Delimiter),
+    (SyntaxKind::L_BRACE_PIPE, "{|", TokenClass::Delimiter),
+    (SyntaxKind::R_BRACE_PIPE, "|}", TokenClass::Delimiter),
+    (SyntaxKind::COMMA, ",", TokenClass::Delimiter),
+    (SyntaxKind::COLON, ":", TokenClass::Delimiter),
+    (SyntaxKind::DOT, ".", TokenClass::Delimiter),
+    (SyntaxKind::ELLIPSIS, "...", TokenClass::Delimiter),
+    (SyntaxKind::QUESTION, "?", TokenClass::Delimiter),
+    (SyntaxKind::SEMICOLON, ";", TokenClass::Delimiter),
+];
+
+impl SyntaxKind {
+    fn fixed_token(self) -> Option<&'static (SyntaxKind, &'static str, TokenClass)> {
+        FIXED_TOKENS.iter().find(|(kind, _, _)| *kind == self)
+    }
+
+    /// All kinds that have a fixed spelling, in declaration order
+    pub fn fixed_tokens() -> impl Iterator<Item = (SyntaxKind, &'static str)> {
+        FIXED_TOKENS.iter().map(|&(kind, text, _)| (kind, text))
+    }
+
+    /// The canonical source text of a keyword, operator or delimiter
+    pub fn text(self) -> Option<&'static str> {
+        self.fixed_token().map(|&(_, text, _)| text)
+    }
+
+    /// Look up the keyword kind spelled `text`, if any
+    pub fn from_keyword(text: &str) -> Option<SyntaxKind> {
+        FIXED_TOKENS
+            .iter()
+            .find(|&&(_, t, class)| class == TokenClass::Keyword && t == text)
+            .map(|&(kind, _, _)| kind)
+    }
+
+    pub fn is_keyword(self) -> bool {
+        matches!(self.fixed_token(), Some((_, _, TokenClass::Keyword)))
+    }
+
+    pub fn is_operator(self) -> bool {
+        matches!(self.fixed_token(), Some((_, _, TokenClass::Operator)))
+    }
+
+    pub fn is_delimiter(self) -> bool {
+        matches!(self.fixed_token(), Some((_, _, TokenClass::Delimiter)))
+    }
+
+    /// Whitespace, newlines and comments: kept in the tree but ignored by the grammar
+    pub fn is_trivia(self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::WHITESPACE
+                | SyntaxKind::NEWLINE
+                | SyntaxKind::COMMENT
+                | SyntaxKind::DOC_COMMENT
+        )
+    }
+
+    /// A node for a type descriptor of any form
+    pub fn is_type_desc(self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::TYPE_DESC
+                | SyntaxKind::NIL_TYPE_DESC
+                | SyntaxKind::OPTIONAL_TYPE_DESC
+                | SyntaxKind::UNION_TYPE_DESC
+                | SyntaxKind::ARRAY_TYPE_DESC
+                | SyntaxKind::TUPLE_TYPE_DESC
+                | SyntaxKind::MAP_TYPE_DESC
+                | SyntaxKind::RECORD_TYPE_DESC
+                | SyntaxKind::SINGLETON_TYPE_DESC
+                | SyntaxKind::PARENTHESIZED_TYPE_DESC
+                | SyntaxKind::TYPE_REFERENCE
+        )
+    }
+}
+
+/// How a kind is named in messages: fixed tokens by their quoted text,
+/// everything else by a lower-case description
+impl fmt::Display for SyntaxKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(text) = self.text() {
+            return write!(f, "'{}'", text);
+        }
+        let name = match self {
+            SyntaxKind::IDENTIFIER => "identifier",
+            SyntaxKind::INTEGER_LITERAL => "integer literal",
+            SyntaxKind::FLOATING_POINT_LITERAL => "floating point literal",
+            SyntaxKind::STRING_LITERAL => "string literal",
+            SyntaxKind::DOC_COMMENT => "documentation comment",
+            SyntaxKind::ERROR => "invalid input",
+            SyntaxKind::EOF => "end of file",
+            _ => {
+                let name = format!("{:?}", self).to_lowercase().replace('_', " ");
+                return f.write_str(&name);
+            }
+        };
+        f.write_str(name)
+    }
+}
+
+impl From<lexer::Token> for SyntaxKind {
+    fn from(token: lexer::Token) -> Self {
+        use lexer::Token as T;
+        match token {
+            T::Import => SyntaxKind::IMPORT_KW,
+            T::Public => SyntaxKind::PUBLIC_KW,
+            T::Function => SyntaxKind::FUNCTION_KW,
+            T::Returns => SyntaxKind::RETURNS_KW,
+            T::Int => SyntaxKind::INT_KW,
+            T::Boolean => SyntaxKind::BOOLEAN_KW,
+            T::Float => SyntaxKind::FLOAT_KW,
+            T::Decimal => SyntaxKind::DECIMAL_KW,
+            T::If => SyntaxKind::IF_KW,
+            T::Else => SyntaxKind::ELSE_KW,
+            T::While => SyntaxKind::WHILE_KW,
+            T::Break => SyntaxKind::BREAK_KW,
+            T::Continue => SyntaxKind::CONTINUE_KW,
+            T::Return => SyntaxKind::RETURN_KW,
+            T::True => SyntaxKind::TRUE_KW,
+            T::False => SyntaxKind::FALSE_KW,
+            T::As => SyntaxKind::AS_KW,
+            T::Const => SyntaxKind::CONST_KW,
+            T::Final => SyntaxKind::FINAL_KW,
+            T::Configurable => SyntaxKind::CONFIGURABLE_KW,
+            T::Type => SyntaxKind::TYPE_KW,
+            T::String => SyntaxKind::STRING_KW,
+            T::Any => SyntaxKind::ANY_KW,
+            T::Anydata => SyntaxKind::ANYDATA_KW,
+            T::Json => SyntaxKind::JSON_KW,
+            T::Never => SyntaxKind::NEVER_KW,
+            T::Readonly => SyntaxKind::READONLY_KW,
+            T::Map => SyntaxKind::MAP_KW,
+            T::Record => SyntaxKind::RECORD_KW,
+            T::Eq => SyntaxKind::EQ,
+            T::NotEq => SyntaxKind::NOT_EQ,
+            T::Lt => SyntaxKind::LT,
+            T::LtEq => SyntaxKind::LT_EQ,
+            T::Gt => SyntaxKind::GT,
+            T::GtEq => SyntaxKind::GT_EQ,
+            T::Plus => SyntaxKind::PLUS,
+            T::Minus => SyntaxKind::MINUS,
+            T::Star => SyntaxKind::STAR,
+            T::Slash => SyntaxKind::SLASH,
+            T::Percent => SyntaxKind::PERCENT,
+            T::Bang => SyntaxKind::BANG,
+            T::Assign => SyntaxKind::ASSIGN,
+            T::Pipe => SyntaxKind::PIPE,
+            T::EqEqEq => SyntaxKind::EXACT_EQ,
+            T::NotEqEq => SyntaxKind::EXACT_NOT_EQ,
+            T::AmpAmp => SyntaxKind::AMP_AMP,
+            T::PipePipe => SyntaxKind::PIPE_PIPE,
+            T::Amp => SyntaxKind::AMP,
+            T::Caret => SyntaxKind::CARET,
+            T::Tilde => SyntaxKind::TILDE,
+            T::LtLt => SyntaxKind::LT_LT,
+            T::LParen => SyntaxKind::L_PAREN,
+            T::RParen => SyntaxKind::R_PAREN,
+            T::LBrace => SyntaxKind::L_BRACE,
+            T::RBrace => SyntaxKind::R_BRACE,
+            T::LBracket => SyntaxKind::L_BRACKET,
+            T::RBracket => SyntaxKind::R_BRACKET,
+            T::LBracePipe => SyntaxKind::L_BRACE_PIPE,
+            T::PipeRBrace => SyntaxKind::R_BRACE_PIPE,
+            T::Comma => SyntaxKind::COMMA,
+            T::Colon => SyntaxKind::COLON,
+            T::Dot => SyntaxKind::DOT,
+            T::Ellipsis => SyntaxKind::ELLIPSIS,
+            T::Question => SyntaxKind::QUESTION,
+            T::Semicolon => SyntaxKind::SEMICOLON,
+            T::Identifier => SyntaxKind::IDENTIFIER,
+            T::IntegerLiteral => SyntaxKind::INTEGER_LITERAL,
+            T::FloatingPointLiteral => SyntaxKind::FLOATING_POINT_LITERAL,
+            T::StringLiteral => SyntaxKind::STRING_LITERAL,
+            // Reported as a lexer error and kept in the tree as an ERROR token
+            T::UnterminatedString => SyntaxKind::ERROR,
+            T::Whitespace => SyntaxKind::WHITESPACE,
+            T::Newline => SyntaxKind::NEWLINE,
+            T::LineComment => SyntaxKind::COMMENT,
+            T::DocComment => SyntaxKind::DOC_COMMENT,
+        }
+    }
 }
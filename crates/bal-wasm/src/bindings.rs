@@ -3,7 +3,7 @@
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
-pub fn parse_source(source: &str) -> Result<JsValue, JsValue> {
+pub fn parse_source(_source: &str) -> Result<JsValue, JsValue> {
     // TODO: Implement parsing
     Ok(JsValue::NULL)
 }
@@ -2,6 +2,7 @@ use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
 pub struct BallerinaCompiler {
+    #[allow(dead_code)]
     source: String,
 }
 